@@ -1,33 +1,40 @@
-use integrity::{split_proof, Felt, VerifierConfiguration};
+use integrity::{
+    split_proof_dyn, DeploymentRegistry, Felt, Hasher, Layout, MemoryVerification, Network,
+    StoneVersion, VerifierConfiguration,
+};
 use starknet_macros::short_string;
 use swiftness::TransformTo;
 use swiftness_stark::types::StarkProof;
 
-/// Sepolia `integrity` verifier contract address.
-const VERIFIER: Felt =
-    Felt::from_hex_unchecked("0x04ce7851f00b6c3289674841fd7a1b96b6fd41ed1edc248faccd672c26371b8c");
-
 fn main() {
     // Parse proof from JSON file
     let proof: StarkProof = swiftness::parse(std::fs::read_to_string("./proof.json").unwrap())
         .unwrap()
         .transform_to();
 
-    // Split proof into multiple steps
-    let proof = split_proof::<swiftness_air::layout::recursive::Layout>(proof).unwrap();
+    // Split proof into multiple steps, using the layout specified in the proof and the hasher and
+    // Stone version from the verifier config (this one requires the `stone6` feature)
+    let proof = split_proof_dyn(
+        proof,
+        VerifierConfiguration::new(
+            Layout::Recursive,
+            Hasher::Keccak160Lsb,
+            StoneVersion::Stone6,
+            MemoryVerification::Cairo1,
+        ),
+    )
+    .unwrap();
+
+    // Configure the calls by supplying a unique job ID
+    let calls = proof.into_calls(short_string!("random_job_id")).unwrap();
 
-    // Configure the calls by supplying a unique job ID and verifier config
-    let calls = proof.into_calls(
-        short_string!("random_job_id"),
-        VerifierConfiguration {
-            layout: short_string!("recursive"),
-            hasher: short_string!("keccak_160_lsb"),
-            stone_version: short_string!("stone6"),
-            memory_verification: short_string!("cairo1"),
-        },
-    );
+    // Look up the Sepolia `integrity` deployment
+    let deployment = DeploymentRegistry::known()
+        .get(&Network::Sepolia)
+        .cloned()
+        .unwrap();
 
     // Flatten the calls into a regular `Vec<Call>` ready for use with `starknet-rs`
-    let calls = calls.collect_calls(VERIFIER);
+    let calls = calls.collect_calls(deployment.fact_registry);
     println!("{} contract calls generated: {:#?}", calls.len(), calls);
 }
@@ -0,0 +1,314 @@
+use std::{error::Error, fs, path::PathBuf, str::FromStr};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use integrity::{
+    compute_verification, reference::format_felts, security_bits, split_and_verify_dyn,
+    split_proof_dyn, Felt, Hasher, IntegrityCalls, Layout, MemoryVerification, StoneVersion,
+    VerifierConfiguration,
+};
+use serde_json::json;
+use starknet_core::utils::{cairo_short_string_to_felt, parse_cairo_short_string};
+use swiftness::TransformTo;
+use swiftness_stark::types::StarkProof;
+
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    version,
+    about = "Split STARK proofs for verification on `integrity`"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Split a proof into verification calls, writing them to a directory
+    Split(SplitCommand),
+    /// Print proof parameters relevant to verification
+    Inspect(InspectCommand),
+    /// Print the fact and verification hashes the fact registry would record for a proof
+    FactHash(FactHashCommand),
+    /// Print the flattened contract calls for verifying a proof as JSON
+    Calls(CallsCommand),
+}
+
+#[derive(Debug, Args)]
+struct SplitCommand {
+    #[command(flatten)]
+    proof: ProofArgs,
+    #[command(flatten)]
+    config: ConfigArgs,
+    #[command(flatten)]
+    job: JobArgs,
+    /// Fully verify the proof off-chain before splitting it
+    #[arg(long)]
+    verify: bool,
+    /// Directory to write the output to
+    #[arg(long, short)]
+    out: PathBuf,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = SplitFormat::Calldata)]
+    format: SplitFormat,
+}
+
+#[derive(Debug, Args)]
+struct InspectCommand {
+    #[command(flatten)]
+    proof: ProofArgs,
+}
+
+#[derive(Debug, Args)]
+struct FactHashCommand {
+    #[command(flatten)]
+    proof: ProofArgs,
+    #[command(flatten)]
+    config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+struct CallsCommand {
+    #[command(flatten)]
+    proof: ProofArgs,
+    #[command(flatten)]
+    config: ConfigArgs,
+    #[command(flatten)]
+    job: JobArgs,
+    /// Fully verify the proof off-chain before splitting it
+    #[arg(long)]
+    verify: bool,
+    /// Address of the `integrity` verifier contract
+    #[arg(long, value_parser = parse_felt)]
+    contract: Felt,
+}
+
+#[derive(Debug, Args)]
+struct ProofArgs {
+    /// Path to the JSON proof file
+    proof: PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct ConfigArgs {
+    /// Cairo layout [default: layout of the proof]
+    #[arg(long)]
+    layout: Option<Layout>,
+    /// Hasher [default: the only enabled hasher]
+    #[arg(long)]
+    hasher: Option<Hasher>,
+    /// Stone prover version [default: the only enabled Stone version]
+    #[arg(long)]
+    stone_version: Option<StoneVersion>,
+    /// Memory verification mode
+    #[arg(long)]
+    memory_verification: MemoryVerification,
+}
+
+#[derive(Debug, Args)]
+#[group(required = true, multiple = false)]
+struct JobArgs {
+    /// Unique job ID, either as a number or a short string
+    #[arg(long, value_parser = parse_felt)]
+    job_id: Option<Felt>,
+    /// Derive the job ID from the proof content and this namespace, either as a number or a short
+    /// string
+    #[arg(long, value_parser = parse_felt)]
+    job_namespace: Option<Felt>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SplitFormat {
+    /// One file per call with space-separated decimal felts
+    Calldata,
+    /// A single `calls.json` file containing all calls
+    Json,
+}
+
+impl ProofArgs {
+    fn load(&self) -> Result<StarkProof, Box<dyn Error>> {
+        Ok(swiftness::parse(fs::read_to_string(&self.proof)?)?.transform_to())
+    }
+}
+
+impl ConfigArgs {
+    fn verifier_config(&self, proof: &StarkProof) -> Result<VerifierConfiguration, Box<dyn Error>> {
+        Ok(VerifierConfiguration::new(
+            match self.layout {
+                Some(layout) => layout,
+                None => Layout::from_felt(proof.public_input.layout)?,
+            },
+            self.hasher.map_or_else(Hasher::only_enabled, Ok)?,
+            self.stone_version
+                .map_or_else(StoneVersion::only_enabled, Ok)?,
+            self.memory_verification,
+        ))
+    }
+}
+
+fn main() {
+    if let Err(err) = run(Cli::parse()) {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    match cli.command {
+        Command::Split(command) => split(command),
+        Command::Inspect(command) => inspect(command),
+        Command::FactHash(command) => fact_hash(command),
+        Command::Calls(command) => calls(command),
+    }
+}
+
+fn split(command: SplitCommand) -> Result<(), Box<dyn Error>> {
+    let calls = split_into_calls(
+        &command.proof,
+        &command.config,
+        &command.job,
+        command.verify,
+    )?;
+
+    fs::create_dir_all(&command.out)?;
+    match command.format {
+        SplitFormat::Calldata => {
+            fs::write(
+                command.out.join("initial.txt"),
+                format_felts(&calls.initial.calldata()),
+            )?;
+            for (index, step) in calls.intermediate_steps.iter().enumerate() {
+                fs::write(
+                    command.out.join(format!("step_{:02}.txt", index)),
+                    format_felts(&step.calldata()),
+                )?;
+            }
+            fs::write(
+                command.out.join("final.txt"),
+                format_felts(&calls.final_step.calldata()),
+            )?;
+        }
+        SplitFormat::Json => {
+            fs::write(
+                command.out.join("calls.json"),
+                serde_json::to_string_pretty(&calls)?,
+            )?;
+        }
+    }
+
+    eprintln!(
+        "Wrote {} calls for job {:#x} to {}",
+        calls.intermediate_steps.len() + 2,
+        calls.initial.job_id,
+        command.out.display()
+    );
+    Ok(())
+}
+
+fn inspect(command: InspectCommand) -> Result<(), Box<dyn Error>> {
+    let proof = command.proof.load()?;
+    let config = &proof.config;
+    let public_input = &proof.public_input;
+
+    let output = json!({
+        "layout": short_string_or_hex(public_input.layout),
+        "log_n_steps": public_input.log_n_steps,
+        "log_trace_domain_size": config.log_trace_domain_size,
+        "log_n_cosets": config.log_n_cosets,
+        "n_queries": config.n_queries,
+        "proof_of_work_bits": config.proof_of_work.n_bits,
+        "security_bits": security_bits(config),
+        "n_verifier_friendly_commitment_layers": config.n_verifier_friendly_commitment_layers,
+        "fri": {
+            "n_layers": config.fri.n_layers,
+            "step_sizes": config.fri.fri_step_sizes,
+            "log_last_layer_degree_bound": config.fri.log_last_layer_degree_bound,
+        },
+        "public_input": {
+            "n_segments": public_input.segments.len(),
+            "main_page_len": public_input.main_page.len(),
+            "n_continuous_pages": public_input.continuous_page_headers.len(),
+        },
+    });
+    println!("{}", serde_json::to_string_pretty(&output)?);
+
+    Ok(())
+}
+
+fn fact_hash(command: FactHashCommand) -> Result<(), Box<dyn Error>> {
+    let proof = command.proof.load()?;
+    let verifier_config = command.config.verifier_config(&proof)?;
+    verifier_config.validate(&proof)?;
+
+    let verification = compute_verification(&proof, &verifier_config)?;
+
+    let output = json!({
+        "program_hash": verification.fact.program_hash,
+        "output_hash": verification.fact.output_hash,
+        "fact_hash": verification.fact.fact_hash,
+        "verifier_config_hash": verification.verifier_config_hash,
+        "security_bits": verification.security_bits,
+        "verification_hash": verification.verification_hash,
+    });
+    println!("{}", serde_json::to_string_pretty(&output)?);
+
+    Ok(())
+}
+
+fn calls(command: CallsCommand) -> Result<(), Box<dyn Error>> {
+    let calls = split_into_calls(
+        &command.proof,
+        &command.config,
+        &command.job,
+        command.verify,
+    )?;
+
+    let output = calls
+        .collect_calls(command.contract)
+        .into_iter()
+        .map(|call| {
+            json!({
+                "to": call.to,
+                "selector": call.selector,
+                "calldata": call.calldata,
+            })
+        })
+        .collect::<Vec<_>>();
+    println!("{}", serde_json::to_string_pretty(&output)?);
+
+    Ok(())
+}
+
+fn split_into_calls(
+    proof: &ProofArgs,
+    config: &ConfigArgs,
+    job: &JobArgs,
+    verify: bool,
+) -> Result<IntegrityCalls, Box<dyn Error>> {
+    let proof = proof.load()?;
+    let verifier_config = config.verifier_config(&proof)?;
+
+    let split_proof = if verify {
+        split_and_verify_dyn(proof, verifier_config)?
+    } else {
+        split_proof_dyn(proof, verifier_config)?
+    };
+    let job_id = match (job.job_id, job.job_namespace) {
+        (Some(job_id), _) => job_id,
+        (None, Some(namespace)) => split_proof.derive_job_id(namespace),
+        (None, None) => unreachable!("clap requires one of the job arguments"),
+    };
+
+    Ok(split_proof.into_calls(job_id)?)
+}
+
+/// Parses a felt from a decimal or hex number, falling back to a Cairo short string.
+fn parse_felt(value: &str) -> Result<Felt, String> {
+    Felt::from_str(value)
+        .or_else(|_| cairo_short_string_to_felt(value))
+        .map_err(|_| format!("`{}` is neither a number nor a valid short string", value))
+}
+
+fn short_string_or_hex(value: Felt) -> String {
+    parse_cairo_short_string(&value).unwrap_or_else(|_| format!("{:#x}", value))
+}
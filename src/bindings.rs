@@ -5,7 +5,7 @@ pub use starknet_core::{
 
 use starknet_core::{
     codec::{Error as CodecError, FeltWriter},
-    types::Call,
+    types::{Call, FunctionCall},
 };
 use swiftness::{
     config::StarkConfig as SwiftnessStarkConfig,
@@ -15,6 +15,7 @@ use swiftness::{
     },
 };
 use swiftness_air::{
+    dynamic::DynamicParams as SwiftnessDynamicParams,
     public_memory::PublicInput as SwiftnessPublicInput,
     trace::{
         config::Config as SwiftnessTracesConfig, Decommitment as SwiftnessTracesDecommitment,
@@ -22,7 +23,7 @@ use swiftness_air::{
     },
     types::{
         AddrValue as SwiftnessAddrValue, ContinuousPageHeader as SwiftnessContinuousPageHeader,
-        SegmentInfo as SwiftnessSegmentInfo,
+        Page as SwiftnessPage, SegmentInfo as SwiftnessSegmentInfo,
     },
 };
 use swiftness_commitment::{
@@ -39,7 +40,10 @@ use swiftness_commitment::{
 };
 use swiftness_fri::{
     config::Config as SwiftnessFriConfig,
-    types::{UnsentCommitment as SwiftnessFriUnsentCommitment, Witness as SwiftnessFriWitness},
+    types::{
+        LayerWitness as SwiftnessFriLayerWitness, UnsentCommitment as SwiftnessFriUnsentCommitment,
+        Witness as SwiftnessFriWitness,
+    },
 };
 use swiftness_pow::{
     config::Config as SwiftnessProofOfWorkConfig,
@@ -47,8 +51,12 @@ use swiftness_pow::{
 };
 use swiftness_stark::types::StarkProof as SwiftnessStarkProof;
 
+/// Number of fields in [`SwiftnessDynamicParams`], whose `From<Vec<u32>>` implementation panics on
+/// any other length.
+const N_DYNAMIC_PARAMS: usize = 340;
+
 /// Entrypoint selector for `verify_proof_initial`.
-const SELECTOR_VERIFY_PROOF_INITIAL_CALL: Felt = Felt::from_raw([
+pub(crate) const SELECTOR_VERIFY_PROOF_INITIAL_CALL: Felt = Felt::from_raw([
     454550947884470974,
     16477582295426715492,
     11685118883294889452,
@@ -56,7 +64,7 @@ const SELECTOR_VERIFY_PROOF_INITIAL_CALL: Felt = Felt::from_raw([
 ]);
 
 /// Entrypoint selector for `verify_proof_step`.
-const SELECTOR_VERIFY_PROOF_STEP_CALL: Felt = Felt::from_raw([
+pub(crate) const SELECTOR_VERIFY_PROOF_STEP_CALL: Felt = Felt::from_raw([
     366928098735624260,
     14431289083207541201,
     10380245210905814816,
@@ -64,15 +72,40 @@ const SELECTOR_VERIFY_PROOF_STEP_CALL: Felt = Felt::from_raw([
 ]);
 
 /// Entrypoint selector for `verify_proof_final_and_register_fact`.
-const SELECTOR_VERIFY_PROOF_FINAL_AND_REGISTER_FACT_CALL: Felt = Felt::from_raw([
+pub(crate) const SELECTOR_VERIFY_PROOF_FINAL_AND_REGISTER_FACT_CALL: Felt = Felt::from_raw([
     123220592339497,
     16622672023924009708,
     11528706201916495377,
     6934812503915115676,
 ]);
 
+/// Entrypoint selector for `verify_proof_full_and_register_fact`.
+const SELECTOR_VERIFY_PROOF_FULL_AND_REGISTER_FACT_CALL: Felt = Felt::from_raw([
+    553971999982945784,
+    3726425414157990085,
+    5003443702821888345,
+    2114048349150835977,
+]);
+
+/// Entrypoint selector for `get_all_verifications_for_fact_hash`.
+const SELECTOR_GET_ALL_VERIFICATIONS_FOR_FACT_HASH_CALL: Felt = Felt::from_raw([
+    277694238985906419,
+    7474710339061364568,
+    17061428801004403275,
+    16896417171533207996,
+]);
+
+/// Entrypoint selector for `get_verification`.
+const SELECTOR_GET_VERIFICATION_CALL: Felt = Felt::from_raw([
+    496327876020634319,
+    1844949990834696310,
+    13302225834669717966,
+    12236610081819831293,
+]);
+
 /// Contract binding for the `verify_proof_initial` contract entrypoint.
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct VerifyProofInitialCall {
     pub job_id: Felt,
@@ -81,7 +114,8 @@ pub struct VerifyProofInitialCall {
 }
 
 /// Contract binding for the `verify_proof_step` contract entrypoint.
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct VerifyProofStepCall {
     pub job_id: Felt,
@@ -91,7 +125,8 @@ pub struct VerifyProofStepCall {
 }
 
 /// Contract binding for the `verify_proof_final_and_register_fact` contract entrypoint.
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct VerifyProofFinalAndRegisterFactCall {
     pub job_id: Felt,
@@ -100,7 +135,54 @@ pub struct VerifyProofFinalAndRegisterFactCall {
     pub last_layer_coefficients: Vec<Felt>,
 }
 
-#[derive(Debug, Clone, Encode)]
+/// Contract binding for the `verify_proof_full_and_register_fact` contract entrypoint, which
+/// verifies a whole proof in a single call.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[starknet(core = "starknet_core")]
+pub struct VerifyProofFullAndRegisterFactCall {
+    pub verifier_config: VerifierConfiguration,
+    pub stark_proof: StarkProofWithSerde,
+}
+
+/// Contract binding for the `get_all_verifications_for_fact_hash` view function.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[starknet(core = "starknet_core")]
+pub struct GetAllVerificationsForFactHashCall {
+    pub fact_hash: Felt,
+}
+
+/// Contract binding for the `get_verification` view function.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[starknet(core = "starknet_core")]
+pub struct GetVerificationCall {
+    pub verification_hash: Felt,
+}
+
+/// A verification of a fact, as returned by `get_all_verifications_for_fact_hash`.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[starknet(core = "starknet_core")]
+pub struct VerificationListElement {
+    pub verification_hash: Felt,
+    pub security_bits: u32,
+    pub verifier_config: VerifierConfiguration,
+}
+
+/// A verification, as returned by `get_verification`.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[starknet(core = "starknet_core")]
+pub struct Verification {
+    pub fact_hash: Felt,
+    pub security_bits: u32,
+    pub verifier_config: VerifierConfiguration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct VerifierConfiguration {
     pub layout: Felt,
@@ -109,7 +191,8 @@ pub struct VerifierConfiguration {
     pub memory_verification: Felt,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct StarkProofWithSerde {
     pub config: StarkConfigWithSerde,
@@ -118,7 +201,8 @@ pub struct StarkProofWithSerde {
     pub witness: StarkWitnessWithSerde,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct StarkConfigWithSerde {
     pub traces: TracesConfigWithSerde,
@@ -131,28 +215,32 @@ pub struct StarkConfigWithSerde {
     pub n_verifier_friendly_commitment_layers: Felt,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct TracesConfigWithSerde {
     pub original: TableCommitmentConfigWithSerde,
     pub interaction: TableCommitmentConfigWithSerde,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct TableCommitmentConfigWithSerde {
     pub n_columns: Felt,
     pub vector: VectorCommitmentConfigWithSerde,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct VectorCommitmentConfigWithSerde {
     pub height: Felt,
     pub n_verifier_friendly_commitment_layers: Felt,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FriConfigWithSerde {
     pub log_input_size: Felt,
     pub n_layers: Felt,
@@ -161,13 +249,15 @@ pub struct FriConfigWithSerde {
     pub log_last_layer_degree_bound: Felt,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct ProofOfWorkConfigWithSerde {
     pub n_bits: u8,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PublicInputWithSerde {
     pub log_n_steps: Felt,
     pub range_check_min: Felt,
@@ -181,21 +271,24 @@ pub struct PublicInputWithSerde {
     pub continuous_page_headers: Vec<ContinuousPageHeader>,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct SegmentInfo {
     pub begin_addr: Felt,
     pub stop_ptr: Felt,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct AddrValue {
     pub address: Felt,
     pub value: Felt,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct ContinuousPageHeader {
     pub start_address: Felt,
@@ -204,7 +297,8 @@ pub struct ContinuousPageHeader {
     pub prod: Felt,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct StarkUnsentCommitmentWithSerde {
     pub traces: TracesUnsentCommitmentWithSerde,
@@ -214,27 +308,31 @@ pub struct StarkUnsentCommitmentWithSerde {
     pub proof_of_work: ProofOfWorkUnsentCommitmentWithSerde,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct TracesUnsentCommitmentWithSerde {
     pub original: Felt,
     pub interaction: Felt,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct FriUnsentCommitmentWithSerde {
     pub inner_layers: Vec<Felt>,
     pub last_layer_coefficients: Vec<Felt>,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct ProofOfWorkUnsentCommitmentWithSerde {
     pub nonce: u64,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct StarkWitnessWithSerde {
     pub traces_decommitment: TracesDecommitmentWithSerde,
@@ -244,43 +342,50 @@ pub struct StarkWitnessWithSerde {
     pub fri_witness: FriWitnessWithSerde,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct TracesDecommitmentWithSerde {
     pub original: TableDecommitmentWithSerde,
     pub interaction: TableDecommitmentWithSerde,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TableDecommitmentWithSerde {
     pub values: Vec<Felt>,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct TracesWitnessWithSerde {
     pub original: TableCommitmentWitnessWithSerde,
     pub interaction: TableCommitmentWitnessWithSerde,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct TableCommitmentWitnessWithSerde {
     pub vector: VectorCommitmentWitnessWithSerde,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VectorCommitmentWitnessWithSerde {
     pub authentications: Vec<Felt>,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct FriWitnessWithSerde {
     pub layers: Vec<Felt>,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct FriVerificationStateConstant {
     pub n_layers: u32,
@@ -290,49 +395,56 @@ pub struct FriVerificationStateConstant {
     pub last_layer_coefficients_hash: Felt,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct FriVerificationStateVariable {
     pub iter: u32,
     pub queries: Vec<FriLayerQuery>,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct FriLayerWitness {
     pub leaves: Vec<Felt>,
     pub table_witness: TableCommitmentWitness,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct TableCommitment {
     pub config: TableCommitmentConfig,
     pub vector_commitment: VectorCommitment,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct TableCommitmentConfig {
     pub n_columns: Felt,
     pub vector: VectorCommitmentConfig,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct VectorCommitment {
     pub config: VectorCommitmentConfig,
     pub commitment_hash: Felt,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct VectorCommitmentConfig {
     pub height: Felt,
     pub n_verifier_friendly_commitment_layers: Felt,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct FriLayerQuery {
     pub index: Felt,
@@ -340,13 +452,15 @@ pub struct FriLayerQuery {
     pub x_inv_value: Felt,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct TableCommitmentWitness {
     pub vector: VectorCommitmentWitness,
 }
 
-#[derive(Debug, Clone, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[starknet(core = "starknet_core")]
 pub struct VectorCommitmentWitness {
     pub authentications: Vec<Felt>,
@@ -409,6 +523,73 @@ impl VerifyProofFinalAndRegisterFactCall {
     }
 }
 
+impl VerifyProofFullAndRegisterFactCall {
+    pub fn call(&self, contract_address: Felt) -> Call {
+        Call {
+            to: contract_address,
+            selector: SELECTOR_VERIFY_PROOF_FULL_AND_REGISTER_FACT_CALL,
+            calldata: self.calldata(),
+        }
+    }
+
+    pub fn calldata(&self) -> Vec<Felt> {
+        let mut calldata = vec![];
+
+        // This type never fails to serialize
+        self.encode(&mut calldata).unwrap();
+
+        calldata
+    }
+}
+
+impl GetAllVerificationsForFactHashCall {
+    pub fn function_call(&self, contract_address: Felt) -> FunctionCall {
+        FunctionCall {
+            contract_address,
+            entry_point_selector: SELECTOR_GET_ALL_VERIFICATIONS_FOR_FACT_HASH_CALL,
+            calldata: self.calldata(),
+        }
+    }
+
+    pub fn calldata(&self) -> Vec<Felt> {
+        vec![self.fact_hash]
+    }
+
+    /// Decodes the felts returned by the view function.
+    pub fn decode_output(output: &[Felt]) -> Result<Vec<VerificationListElement>, CodecError> {
+        Vec::<VerificationListElement>::decode(output)
+    }
+}
+
+impl GetVerificationCall {
+    pub fn function_call(&self, contract_address: Felt) -> FunctionCall {
+        FunctionCall {
+            contract_address,
+            entry_point_selector: SELECTOR_GET_VERIFICATION_CALL,
+            calldata: self.calldata(),
+        }
+    }
+
+    pub fn calldata(&self) -> Vec<Felt> {
+        vec![self.verification_hash]
+    }
+
+    /// Decodes the felts returned by the view function, which are `None` if the verification hash
+    /// is not registered.
+    pub fn decode_output(output: &[Felt]) -> Result<Option<Verification>, CodecError> {
+        Option::<Verification>::decode(output)
+    }
+
+    /// Decodes the felts returned by the view function into whether the verification hash is
+    /// registered, additionally checking client-side that the returned verification hashes to it.
+    ///
+    /// The contract has no dedicated view for this, so it's derived from `get_verification`.
+    pub fn decode_is_registered(&self, output: &[Felt]) -> Result<bool, CodecError> {
+        Ok(Self::decode_output(output)?
+            .is_some_and(|verification| verification.verification_hash() == self.verification_hash))
+    }
+}
+
 impl From<SwiftnessStarkProof> for StarkProofWithSerde {
     fn from(value: SwiftnessStarkProof) -> Self {
         Self {
@@ -631,12 +812,284 @@ impl From<SwiftnessVectorCommitmentWitness> for VectorCommitmentWitnessWithSerde
 }
 
 impl From<SwiftnessFriWitness> for FriWitnessWithSerde {
-    fn from(_value: SwiftnessFriWitness) -> Self {
-        // This is technically wrong. It's done here as `layers` should be empty when splitting
-        // proofs. However, the proper way would be to correctly convert the field and let the
-        // caller empty the layers.
-        // TODO: idiomatically convert the type
-        Self { layers: vec![] }
+    fn from(value: SwiftnessFriWitness) -> Self {
+        // The canonical type flattens each layer into its length-prefixed leaves followed by its
+        // length-prefixed authentications.
+        let mut layers = vec![];
+        for layer in value.layers {
+            let authentications = layer.table_witness.vector.authentications;
+
+            layers.push(layer.leaves.len().into());
+            layers.extend(layer.leaves);
+            layers.push(authentications.len().into());
+            layers.extend(authentications);
+        }
+
+        Self { layers }
+    }
+}
+
+impl TryFrom<StarkProofWithSerde> for SwiftnessStarkProof {
+    type Error = CodecError;
+
+    fn try_from(value: StarkProofWithSerde) -> Result<Self, Self::Error> {
+        Ok(Self {
+            config: value.config.into(),
+            public_input: value.public_input.try_into()?,
+            unsent_commitment: value.unsent_commitment.into(),
+            witness: value.witness.try_into()?,
+        })
+    }
+}
+
+impl From<StarkConfigWithSerde> for SwiftnessStarkConfig {
+    fn from(value: StarkConfigWithSerde) -> Self {
+        Self {
+            traces: value.traces.into(),
+            composition: value.composition.into(),
+            fri: value.fri.into(),
+            proof_of_work: value.proof_of_work.into(),
+            log_trace_domain_size: value.log_trace_domain_size,
+            n_queries: value.n_queries,
+            log_n_cosets: value.log_n_cosets,
+            n_verifier_friendly_commitment_layers: value.n_verifier_friendly_commitment_layers,
+        }
+    }
+}
+
+impl From<TracesConfigWithSerde> for SwiftnessTracesConfig {
+    fn from(value: TracesConfigWithSerde) -> Self {
+        Self {
+            original: value.original.into(),
+            interaction: value.interaction.into(),
+        }
+    }
+}
+
+impl From<TableCommitmentConfigWithSerde> for SwiftnessTableCommitmentConfig {
+    fn from(value: TableCommitmentConfigWithSerde) -> Self {
+        Self {
+            n_columns: value.n_columns,
+            vector: value.vector.into(),
+        }
+    }
+}
+
+impl From<VectorCommitmentConfigWithSerde> for SwiftnessVectorCommitmentConfig {
+    fn from(value: VectorCommitmentConfigWithSerde) -> Self {
+        Self {
+            height: value.height,
+            n_verifier_friendly_commitment_layers: value.n_verifier_friendly_commitment_layers,
+        }
+    }
+}
+
+impl From<FriConfigWithSerde> for SwiftnessFriConfig {
+    fn from(value: FriConfigWithSerde) -> Self {
+        Self {
+            log_input_size: value.log_input_size,
+            n_layers: value.n_layers,
+            inner_layers: value.inner_layers.into_iter().map(|l| l.into()).collect(),
+            fri_step_sizes: value.fri_step_sizes,
+            log_last_layer_degree_bound: value.log_last_layer_degree_bound,
+        }
+    }
+}
+
+impl From<ProofOfWorkConfigWithSerde> for SwiftnessProofOfWorkConfig {
+    fn from(value: ProofOfWorkConfigWithSerde) -> Self {
+        Self {
+            n_bits: value.n_bits,
+        }
+    }
+}
+
+impl TryFrom<PublicInputWithSerde> for SwiftnessPublicInput {
+    type Error = CodecError;
+
+    fn try_from(value: PublicInputWithSerde) -> Result<Self, Self::Error> {
+        // Layouts without dynamic params are encoded with an empty list.
+        let dynamic_params = if value.dynamic_params.is_empty() {
+            None
+        } else {
+            if value.dynamic_params.len() != N_DYNAMIC_PARAMS {
+                return Err(CodecError::length_mismatch(
+                    N_DYNAMIC_PARAMS,
+                    value.dynamic_params.len(),
+                ));
+            }
+
+            let dynamic_params = value
+                .dynamic_params
+                .iter()
+                .map(|param| {
+                    u32::try_from(*param).map_err(|_| CodecError::value_out_of_range(param, "u32"))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Some(SwiftnessDynamicParams::from(dynamic_params))
+        };
+
+        Ok(Self {
+            log_n_steps: value.log_n_steps,
+            range_check_min: value.range_check_min,
+            range_check_max: value.range_check_max,
+            layout: value.layout,
+            dynamic_params,
+            segments: value.segments.into_iter().map(Into::into).collect(),
+            padding_addr: value.padding_addr,
+            padding_value: value.padding_value,
+            main_page: SwiftnessPage(value.main_page.into_iter().map(Into::into).collect()),
+            continuous_page_headers: value
+                .continuous_page_headers
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        })
+    }
+}
+
+impl From<SegmentInfo> for SwiftnessSegmentInfo {
+    fn from(value: SegmentInfo) -> Self {
+        Self {
+            begin_addr: value.begin_addr,
+            stop_ptr: value.stop_ptr,
+        }
+    }
+}
+
+impl From<AddrValue> for SwiftnessAddrValue {
+    fn from(value: AddrValue) -> Self {
+        Self {
+            address: value.address,
+            value: value.value,
+        }
+    }
+}
+
+impl From<ContinuousPageHeader> for SwiftnessContinuousPageHeader {
+    fn from(value: ContinuousPageHeader) -> Self {
+        Self {
+            start_address: value.start_address,
+            size: value.size,
+            hash: value.hash,
+            prod: value.prod,
+        }
+    }
+}
+
+impl From<StarkUnsentCommitmentWithSerde> for SwiftnessStarkUnsentCommitment {
+    fn from(value: StarkUnsentCommitmentWithSerde) -> Self {
+        Self {
+            traces: value.traces.into(),
+            composition: value.composition,
+            oods_values: value.oods_values,
+            fri: value.fri.into(),
+            proof_of_work: value.proof_of_work.into(),
+        }
+    }
+}
+
+impl From<TracesUnsentCommitmentWithSerde> for SwiftnessTracesUnsentCommitment {
+    fn from(value: TracesUnsentCommitmentWithSerde) -> Self {
+        Self {
+            original: value.original,
+            interaction: value.interaction,
+        }
+    }
+}
+
+impl From<FriUnsentCommitmentWithSerde> for SwiftnessFriUnsentCommitment {
+    fn from(value: FriUnsentCommitmentWithSerde) -> Self {
+        Self {
+            inner_layers: value.inner_layers,
+            last_layer_coefficients: value.last_layer_coefficients,
+        }
+    }
+}
+
+impl From<ProofOfWorkUnsentCommitmentWithSerde> for SwiftnessProofOfWorkUnsentCommitment {
+    fn from(value: ProofOfWorkUnsentCommitmentWithSerde) -> Self {
+        Self { nonce: value.nonce }
+    }
+}
+
+impl TryFrom<StarkWitnessWithSerde> for SwiftnessStarkWitness {
+    type Error = CodecError;
+
+    fn try_from(value: StarkWitnessWithSerde) -> Result<Self, Self::Error> {
+        Ok(Self {
+            traces_decommitment: value.traces_decommitment.into(),
+            traces_witness: value.traces_witness.into(),
+            composition_decommitment: value.composition_decommitment.into(),
+            composition_witness: value.composition_witness.into(),
+            fri_witness: value.fri_witness.try_into()?,
+        })
+    }
+}
+
+impl From<TracesDecommitmentWithSerde> for SwiftnessTracesDecommitment {
+    fn from(value: TracesDecommitmentWithSerde) -> Self {
+        Self {
+            original: value.original.into(),
+            interaction: value.interaction.into(),
+        }
+    }
+}
+
+impl From<TableDecommitmentWithSerde> for SwiftnessTableDecommitment {
+    fn from(value: TableDecommitmentWithSerde) -> Self {
+        Self {
+            values: value.values,
+        }
+    }
+}
+
+impl From<TracesWitnessWithSerde> for SwiftnessTracesWitness {
+    fn from(value: TracesWitnessWithSerde) -> Self {
+        Self {
+            original: value.original.into(),
+            interaction: value.interaction.into(),
+        }
+    }
+}
+
+impl From<TableCommitmentWitnessWithSerde> for SwiftnessTableCommitmentWitness {
+    fn from(value: TableCommitmentWitnessWithSerde) -> Self {
+        Self {
+            vector: value.vector.into(),
+        }
+    }
+}
+
+impl From<VectorCommitmentWitnessWithSerde> for SwiftnessVectorCommitmentWitness {
+    fn from(value: VectorCommitmentWitnessWithSerde) -> Self {
+        Self {
+            authentications: value.authentications,
+        }
+    }
+}
+
+impl TryFrom<FriWitnessWithSerde> for SwiftnessFriWitness {
+    type Error = CodecError;
+
+    fn try_from(value: FriWitnessWithSerde) -> Result<Self, Self::Error> {
+        let mut iter = value.layers.iter();
+
+        let mut layers = vec![];
+        while iter.len() != 0 {
+            // Both lists are length-prefixed, matching the `Vec<Felt>` encoding.
+            let leaves = Vec::<Felt>::decode_iter(&mut iter)?;
+            let authentications = Vec::<Felt>::decode_iter(&mut iter)?;
+
+            layers.push(SwiftnessFriLayerWitness {
+                leaves,
+                table_witness: SwiftnessTableCommitmentWitness {
+                    vector: SwiftnessVectorCommitmentWitness { authentications },
+                },
+            });
+        }
+
+        Ok(Self { layers })
     }
 }
 
@@ -719,3 +1172,280 @@ impl Encode for VectorCommitmentWitnessWithSerde {
         self.authentications.encode(writer)
     }
 }
+
+impl<'a> Decode<'a> for FriConfigWithSerde {
+    fn decode_iter<T>(iter: &mut T) -> Result<Self, CodecError>
+    where
+        T: Iterator<Item = &'a Felt>,
+    {
+        let log_input_size = Felt::decode_iter(iter)?;
+        let n_layers = Felt::decode_iter(iter)?;
+
+        // The length prefix counts felts instead of elements, as each inner layer config is
+        // flattened into 3 felts.
+        let inner_layers_len = decode_usize(iter)?;
+        if inner_layers_len % 3 != 0 {
+            return Err(CodecError::custom(
+                "FRI inner layer config length is not a multiple of 3",
+            ));
+        }
+        let inner_layers = (0..(inner_layers_len / 3))
+            .map(|_| TableCommitmentConfigWithSerde::decode_iter(iter))
+            .collect::<Result<_, _>>()?;
+
+        let fri_step_sizes = Vec::<Felt>::decode_iter(iter)?;
+        let log_last_layer_degree_bound = Felt::decode_iter(iter)?;
+
+        Ok(Self {
+            log_input_size,
+            n_layers,
+            inner_layers,
+            fri_step_sizes,
+            log_last_layer_degree_bound,
+        })
+    }
+}
+
+impl<'a> Decode<'a> for PublicInputWithSerde {
+    fn decode_iter<T>(iter: &mut T) -> Result<Self, CodecError>
+    where
+        T: Iterator<Item = &'a Felt>,
+    {
+        let log_n_steps = Felt::decode_iter(iter)?;
+        let range_check_min = Felt::decode_iter(iter)?;
+        let range_check_max = Felt::decode_iter(iter)?;
+        let layout = Felt::decode_iter(iter)?;
+        let dynamic_params = Vec::<Felt>::decode_iter(iter)?;
+
+        // `n_segments` and `segments`
+        let segments = decode_double_prefixed(iter, 2)?;
+
+        let padding_addr = Felt::decode_iter(iter)?;
+        let padding_value = Felt::decode_iter(iter)?;
+
+        // `main_page_len` and `main_page`
+        let main_page = decode_double_prefixed(iter, 2)?;
+
+        // `n_continuous_pages` and `continuous_page_headers`
+        let continuous_page_headers = decode_double_prefixed(iter, 4)?;
+
+        Ok(Self {
+            log_n_steps,
+            range_check_min,
+            range_check_max,
+            layout,
+            dynamic_params,
+            segments,
+            padding_addr,
+            padding_value,
+            main_page,
+            continuous_page_headers,
+        })
+    }
+}
+
+impl<'a> Decode<'a> for TableDecommitmentWithSerde {
+    fn decode_iter<T>(iter: &mut T) -> Result<Self, CodecError>
+    where
+        T: Iterator<Item = &'a Felt>,
+    {
+        // `n_values` and `values`
+        Ok(Self {
+            values: decode_double_prefixed(iter, 1)?,
+        })
+    }
+}
+
+impl<'a> Decode<'a> for VectorCommitmentWitnessWithSerde {
+    fn decode_iter<T>(iter: &mut T) -> Result<Self, CodecError>
+    where
+        T: Iterator<Item = &'a Felt>,
+    {
+        // `n_authentications` and `authentications`
+        Ok(Self {
+            authentications: decode_double_prefixed(iter, 1)?,
+        })
+    }
+}
+
+fn decode_usize<'a, T>(iter: &mut T) -> Result<usize, CodecError>
+where
+    T: Iterator<Item = &'a Felt>,
+{
+    let value = iter.next().ok_or_else(CodecError::input_exhausted)?;
+    usize::try_from(*value).map_err(|_| CodecError::value_out_of_range(value, "usize"))
+}
+
+/// Decodes a list that is prefixed by both its element count and its felt length, where each
+/// element takes `felts_per_element` felts.
+fn decode_double_prefixed<'a, T, E>(
+    iter: &mut T,
+    felts_per_element: usize,
+) -> Result<Vec<E>, CodecError>
+where
+    T: Iterator<Item = &'a Felt>,
+    E: Decode<'a>,
+{
+    let n_elements = decode_usize(iter)?;
+    let n_felts = decode_usize(iter)?;
+
+    let expected_n_felts = n_elements
+        .checked_mul(felts_per_element)
+        .ok_or_else(|| CodecError::value_out_of_range(n_elements, "usize"))?;
+    if n_felts != expected_n_felts {
+        return Err(CodecError::length_mismatch(expected_n_felts, n_felts));
+    }
+
+    (0..n_elements).map(|_| E::decode_iter(iter)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+
+    use super::*;
+    #[cfg(all(feature = "recursive", feature = "keccak_160_lsb", feature = "stone5"))]
+    use crate::fixtures;
+
+    fn assert_round_trip<T>(value: &T)
+    where
+        T: Encode + for<'a> Decode<'a> + PartialEq + Debug,
+    {
+        let mut felts = vec![];
+        value.encode(&mut felts).unwrap();
+
+        let mut iter = felts.iter();
+        assert_eq!(&T::decode_iter(&mut iter).unwrap(), value);
+        assert_eq!(iter.count(), 0);
+    }
+
+    #[cfg(all(feature = "recursive", feature = "keccak_160_lsb", feature = "stone5"))]
+    #[test]
+    fn proof_round_trip() {
+        // Converted from the full proof so that the FRI witness layers are populated.
+        let proof = StarkProofWithSerde::from(fixtures::proof());
+        assert!(!proof.witness.fri_witness.layers.is_empty());
+
+        assert_round_trip(&proof);
+        assert_round_trip(&proof.config);
+        assert_round_trip(&proof.config.traces);
+        assert_round_trip(&proof.config.composition);
+        assert_round_trip(&proof.config.fri);
+        assert_round_trip(&proof.config.proof_of_work);
+        assert_round_trip(&proof.public_input);
+        assert_round_trip(&proof.public_input.segments[0]);
+        assert_round_trip(&proof.public_input.main_page[0]);
+        assert_round_trip(&proof.unsent_commitment);
+        assert_round_trip(&proof.unsent_commitment.traces);
+        assert_round_trip(&proof.unsent_commitment.fri);
+        assert_round_trip(&proof.unsent_commitment.proof_of_work);
+        assert_round_trip(&proof.witness);
+        assert_round_trip(&proof.witness.traces_decommitment);
+        assert_round_trip(&proof.witness.traces_decommitment.original);
+        assert_round_trip(&proof.witness.traces_witness);
+        assert_round_trip(&proof.witness.composition_decommitment);
+        assert_round_trip(&proof.witness.composition_witness);
+        assert_round_trip(&proof.witness.composition_witness.vector);
+        assert_round_trip(&proof.witness.fri_witness);
+    }
+
+    #[test]
+    fn continuous_page_header_round_trip() {
+        assert_round_trip(&ContinuousPageHeader {
+            start_address: Felt::ONE,
+            size: Felt::TWO,
+            hash: Felt::THREE,
+            prod: Felt::from(4u32),
+        });
+    }
+
+    #[cfg(all(feature = "recursive", feature = "keccak_160_lsb", feature = "stone5"))]
+    #[test]
+    fn calls_round_trip() {
+        let calls = fixtures::calls(Felt::ONE);
+
+        assert_round_trip(&calls.initial);
+        for step in &calls.intermediate_steps {
+            assert_round_trip(step);
+            assert_round_trip(&step.state_constant);
+            assert_round_trip(&step.state_constant.commitment[0]);
+            assert_round_trip(&step.state_constant.commitment[0].config);
+            assert_round_trip(&step.state_constant.commitment[0].vector_commitment);
+            assert_round_trip(&step.state_variable);
+            assert_round_trip(&step.state_variable.queries[0]);
+            assert_round_trip(&step.witness);
+            assert_round_trip(&step.witness.table_witness);
+            assert_round_trip(&step.witness.table_witness.vector);
+        }
+        assert_round_trip(&calls.final_step);
+
+        assert_round_trip(&VerifyProofFullAndRegisterFactCall {
+            verifier_config: fixtures::verifier_config(),
+            stark_proof: fixtures::proof().into(),
+        });
+    }
+
+    #[cfg(all(feature = "recursive", feature = "keccak_160_lsb", feature = "stone5"))]
+    #[test]
+    fn view_round_trip() {
+        let verifier_config = fixtures::verifier_config();
+
+        assert_round_trip(&verifier_config);
+        assert_round_trip(&GetAllVerificationsForFactHashCall {
+            fact_hash: Felt::ONE,
+        });
+        assert_round_trip(&GetVerificationCall {
+            verification_hash: Felt::TWO,
+        });
+        assert_round_trip(&VerificationListElement {
+            verification_hash: Felt::THREE,
+            security_bits: 50,
+            verifier_config: verifier_config.clone(),
+        });
+        assert_round_trip(&Verification {
+            fact_hash: Felt::ONE,
+            security_bits: 50,
+            verifier_config,
+        });
+    }
+
+    #[test]
+    fn double_prefixed_length_mismatch() {
+        let mut felts = vec![];
+        TableDecommitmentWithSerde {
+            values: vec![Felt::ONE, Felt::TWO],
+        }
+        .encode(&mut felts)
+        .unwrap();
+
+        // The felt length prefix no longer matches the element count.
+        felts[1] += Felt::ONE;
+        assert!(TableDecommitmentWithSerde::decode(&felts).is_err());
+    }
+
+    #[cfg(all(feature = "recursive", feature = "keccak_160_lsb", feature = "stone5"))]
+    #[test]
+    fn decode_is_registered() {
+        let verification = Verification {
+            fact_hash: Felt::ONE,
+            security_bits: 50,
+            verifier_config: fixtures::verifier_config(),
+        };
+        let call = GetVerificationCall {
+            verification_hash: verification.verification_hash(),
+        };
+
+        let mut output = vec![];
+        Some(verification).encode(&mut output).unwrap();
+        assert!(call.decode_is_registered(&output).unwrap());
+
+        let other_call = GetVerificationCall {
+            verification_hash: Felt::TWO,
+        };
+        assert!(!other_call.decode_is_registered(&output).unwrap());
+
+        let mut output = vec![];
+        Option::<Verification>::None.encode(&mut output).unwrap();
+        assert!(!call.decode_is_registered(&output).unwrap());
+    }
+}
@@ -0,0 +1,177 @@
+use starknet_core::types::Felt;
+use starknet_crypto::{pedersen_hash, poseidon_hash_many};
+use swiftness::{
+    commit::Error as CommitError, config::StarkConfig, oods::verify_oods,
+    types::StarkUnsentCommitment,
+};
+use swiftness_air::{domains::StarkDomains, layout::LayoutTrait, public_memory::PublicInput};
+use swiftness_commitment::table::commit::table_commit;
+use swiftness_fri::fri::fri_commit;
+use swiftness_pow::pow::Error as PowError;
+use swiftness_stark::types::StarkCommitment;
+use swiftness_transcript::transcript::Transcript;
+
+use crate::{
+    config::{ConfigError, Hasher, StoneVersion},
+    crypto::verify_pow,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum StarkCommitError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Commit(#[from] CommitError),
+}
+
+/// Computes the initial hash seed of the Fiat-Shamir transcript from the public input.
+///
+/// Stone 6 additionally commits to the number of verifier friendly commitment layers.
+pub(crate) fn public_input_hash(
+    public_input: &PublicInput,
+    n_verifier_friendly_commitment_layers: Felt,
+    stone_version: StoneVersion,
+) -> Felt {
+    let mut main_page_hash = Felt::ZERO;
+    for memory in public_input.main_page.iter() {
+        main_page_hash = pedersen_hash(&main_page_hash, &memory.address);
+        main_page_hash = pedersen_hash(&main_page_hash, &memory.value);
+    }
+    main_page_hash = pedersen_hash(
+        &main_page_hash,
+        &(Felt::TWO * Felt::from(public_input.main_page.len())),
+    );
+
+    let mut hash_data = vec![];
+    if stone_version == StoneVersion::Stone6 {
+        hash_data.push(n_verifier_friendly_commitment_layers);
+    }
+    hash_data.extend([
+        public_input.log_n_steps,
+        public_input.range_check_min,
+        public_input.range_check_max,
+        public_input.layout,
+    ]);
+
+    if let Some(dynamic_params) = &public_input.dynamic_params {
+        hash_data.extend(
+            Vec::<u32>::from(dynamic_params.clone())
+                .into_iter()
+                .map(Felt::from),
+        );
+    }
+
+    hash_data.extend(
+        public_input
+            .segments
+            .iter()
+            .flat_map(|segment| [segment.begin_addr, segment.stop_ptr]),
+    );
+
+    hash_data.push(public_input.padding_addr);
+    hash_data.push(public_input.padding_value);
+    hash_data.push(Felt::from(public_input.continuous_page_headers.len() + 1));
+
+    // Main page.
+    hash_data.push(Felt::from(public_input.main_page.len()));
+    hash_data.push(main_page_hash);
+
+    // Add the rest of the pages.
+    hash_data.extend(
+        public_input
+            .continuous_page_headers
+            .iter()
+            .flat_map(|header| [header.start_address, header.size, header.hash]),
+    );
+
+    poseidon_hash_many(&hash_data)
+}
+
+/// STARK commitment phase.
+///
+/// This is the same as `swiftness::commit::stark_commit` except that the proof of work hasher is
+/// selected at runtime.
+pub(crate) fn stark_commit<Layout: LayoutTrait>(
+    transcript: &mut Transcript,
+    public_input: &PublicInput,
+    unsent_commitment: &StarkUnsentCommitment,
+    config: &StarkConfig,
+    stark_domains: &StarkDomains,
+    hasher: Hasher,
+) -> Result<StarkCommitment<Layout::InteractionElements>, StarkCommitError> {
+    // Read the commitment of the 'traces' component.
+    let traces_commitment =
+        Layout::traces_commit(transcript, &unsent_commitment.traces, config.traces.clone());
+
+    // Generate interaction values after traces commitment.
+    let composition_alpha = transcript.random_felt_to_prover();
+    let traces_coefficients = powers_array(composition_alpha, Layout::N_CONSTRAINTS);
+
+    // Read composition commitment.
+    let composition_commitment = table_commit(
+        transcript,
+        unsent_commitment.composition,
+        config.composition.clone(),
+    );
+
+    // Generate interaction values after composition.
+    let interaction_after_composition = transcript.random_felt_to_prover();
+
+    // Read OODS values.
+    transcript.read_felt_vector_from_prover(&unsent_commitment.oods_values);
+
+    // Check that the trace and the composition agree at oods_point.
+    verify_oods::<Layout>(
+        &unsent_commitment.oods_values,
+        &traces_commitment.interaction_elements,
+        public_input,
+        &traces_coefficients,
+        &interaction_after_composition,
+        &stark_domains.trace_domain_size,
+        &stark_domains.trace_generator,
+    )
+    .map_err(CommitError::from)?;
+
+    // Generate interaction values after OODS.
+    let oods_alpha = transcript.random_felt_to_prover();
+    let oods_coefficients = powers_array(oods_alpha, Layout::MASK_SIZE + Layout::CONSTRAINT_DEGREE);
+
+    // Read fri commitment.
+    let fri_commitment = fri_commit(
+        transcript,
+        unsent_commitment.fri.clone(),
+        config.fri.clone(),
+    );
+
+    // Proof of work commitment phase.
+    if !verify_pow(
+        hasher,
+        *transcript.digest(),
+        config.proof_of_work.n_bits,
+        unsent_commitment.proof_of_work.nonce,
+    )? {
+        return Err(CommitError::POW(PowError::ProofOfWorkFail).into());
+    }
+    transcript.read_uint64_from_prover(unsent_commitment.proof_of_work.nonce);
+
+    Ok(StarkCommitment {
+        traces: traces_commitment,
+        composition: composition_commitment,
+        interaction_after_composition,
+        oods_values: unsent_commitment.oods_values.clone(),
+        interaction_after_oods: oods_coefficients,
+        fri: fri_commitment,
+    })
+}
+
+fn powers_array(alpha: Felt, n: usize) -> Vec<Felt> {
+    let mut array = Vec::with_capacity(n);
+    let mut value = Felt::ONE;
+
+    for _ in 0..n {
+        array.push(value);
+        value *= alpha;
+    }
+
+    array
+}
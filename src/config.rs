@@ -0,0 +1,323 @@
+use std::{fmt::Display, str::FromStr};
+
+use starknet_core::{types::Felt, utils::parse_cairo_short_string};
+use swiftness_stark::types::StarkProof;
+
+use crate::bindings::VerifierConfiguration;
+
+/// Errors from interpreting a [`VerifierConfiguration`].
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("unknown {kind} `{value}`")]
+    Unknown { kind: &'static str, value: String },
+    #[error("{kind} `{name}` is not enabled: enable the `{name}` cargo feature")]
+    NotEnabled {
+        kind: &'static str,
+        name: &'static str,
+    },
+    #[error("no {kind} is enabled: enable one of the {kind} cargo features")]
+    NoneEnabled { kind: &'static str },
+    #[error("multiple {kind}s are enabled, so one must be chosen explicitly")]
+    Ambiguous { kind: &'static str },
+    #[error("{kind} mismatch: configuration has `{config}` but the proof has `{proof}`")]
+    Mismatch {
+        kind: &'static str,
+        config: String,
+        proof: String,
+    },
+}
+
+/// Defines a configuration enum whose variants are identified by the short strings used by the
+/// `integrity` contract, along with whether each variant is compiled in.
+macro_rules! config_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $kind:literal {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident => $short_string:literal, enabled = $enabled:expr
+            ),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )+
+        }
+
+        impl $name {
+            /// All variants supported by the `integrity` verifier.
+            pub const ALL: &'static [Self] = &[$(Self::$variant),+];
+
+            #[doc = concat!("Name of the ", $kind, " as used by the `integrity` contract.")]
+            pub const fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $short_string,)+
+                }
+            }
+
+            #[doc = concat!("Whether support for the ", $kind, " is compiled in.")]
+            pub const fn is_enabled(&self) -> bool {
+                match self {
+                    $(Self::$variant => $enabled,)+
+                }
+            }
+
+            #[doc = concat!("Short string representation of the ", $kind, ".")]
+            pub fn to_felt(&self) -> Felt {
+                Felt::from_bytes_be_slice(self.name().as_bytes())
+            }
+
+            #[doc = concat!(
+                "Parses the ", $kind, " from its short string representation, failing if it's ",
+                "unknown. Support for it doesn't need to be compiled in."
+            )]
+            pub fn from_felt(felt: Felt) -> Result<Self, ConfigError> {
+                Self::ALL
+                    .iter()
+                    .find(|value| value.to_felt() == felt)
+                    .ok_or_else(|| ConfigError::Unknown {
+                        kind: $kind,
+                        value: felt_to_string(felt),
+                    })
+                    .copied()
+            }
+
+            #[doc = concat!("Gets the only enabled ", $kind, ", if exactly one is enabled.")]
+            pub fn only_enabled() -> Result<Self, ConfigError> {
+                let mut enabled = Self::ALL.iter().filter(|value| value.is_enabled());
+                match (enabled.next(), enabled.next()) {
+                    (Some(value), None) => Ok(*value),
+                    (Some(_), Some(_)) => Err(ConfigError::Ambiguous { kind: $kind }),
+                    (None, _) => Err(ConfigError::NoneEnabled { kind: $kind }),
+                }
+            }
+
+            #[doc = concat!("Fails if support for the ", $kind, " is not compiled in.")]
+            pub fn ensure_enabled(self) -> Result<Self, ConfigError> {
+                if self.is_enabled() {
+                    Ok(self)
+                } else {
+                    Err(ConfigError::NotEnabled {
+                        kind: $kind,
+                        name: self.name(),
+                    })
+                }
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.name())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ConfigError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::ALL
+                    .iter()
+                    .find(|value| value.name() == s)
+                    .ok_or_else(|| ConfigError::Unknown {
+                        kind: $kind,
+                        value: s.to_owned(),
+                    })
+                    .copied()
+            }
+        }
+
+        impl From<$name> for Felt {
+            fn from(value: $name) -> Self {
+                value.to_felt()
+            }
+        }
+
+        impl TryFrom<Felt> for $name {
+            type Error = ConfigError;
+
+            fn try_from(value: Felt) -> Result<Self, Self::Error> {
+                Self::from_felt(value)
+            }
+        }
+    };
+}
+
+config_enum! {
+    /// Cairo layout the proof was generated with.
+    ///
+    /// Only one layout can be enabled per build.
+    Layout, "layout" {
+        Dex => "dex", enabled = cfg!(feature = "dex"),
+        Dynamic => "dynamic", enabled = cfg!(feature = "dynamic"),
+        Recursive => "recursive", enabled = cfg!(feature = "recursive"),
+        RecursiveWithPoseidon => "recursive_with_poseidon",
+            enabled = cfg!(feature = "recursive_with_poseidon"),
+        Small => "small", enabled = cfg!(feature = "small"),
+        Starknet => "starknet", enabled = cfg!(feature = "starknet"),
+        StarknetWithKeccak => "starknet_with_keccak",
+            enabled = cfg!(feature = "starknet_with_keccak"),
+    }
+}
+
+config_enum! {
+    /// Hash function used for the non-verifier-friendly commitment layers and proof of work.
+    Hasher, "hasher" {
+        Keccak160Lsb => "keccak_160_lsb", enabled = cfg!(feature = "keccak_160_lsb"),
+        Keccak248Lsb => "keccak_248_lsb", enabled = cfg!(feature = "keccak_248_lsb"),
+        Blake2s160Lsb => "blake2s_160_lsb", enabled = cfg!(feature = "blake2s_160_lsb"),
+        Blake2s248Lsb => "blake2s_248_lsb", enabled = cfg!(feature = "blake2s_248_lsb"),
+    }
+}
+
+config_enum! {
+    /// Version of the Stone prover that generated the proof.
+    StoneVersion, "stone version" {
+        Stone5 => "stone5", enabled = cfg!(feature = "stone5"),
+        Stone6 => "stone6", enabled = cfg!(feature = "stone6"),
+    }
+}
+
+config_enum! {
+    /// How the verifier derives the fact from the proof's public memory.
+    MemoryVerification, "memory verification mode" {
+        /// Requires the proof to be generated by the bootloader.
+        Strict => "strict", enabled = true,
+        /// Like [`MemoryVerification::Strict`] but without checking the bootloader.
+        Relaxed => "relaxed", enabled = true,
+        /// Treats the main page as a Cairo 1 program followed by its output.
+        Cairo1 => "cairo1", enabled = true,
+    }
+}
+
+impl VerifierConfiguration {
+    /// Creates a [`VerifierConfiguration`] from typed values.
+    pub fn new(
+        layout: Layout,
+        hasher: Hasher,
+        stone_version: StoneVersion,
+        memory_verification: MemoryVerification,
+    ) -> Self {
+        Self {
+            layout: layout.into(),
+            hasher: hasher.into(),
+            stone_version: stone_version.into(),
+            memory_verification: memory_verification.into(),
+        }
+    }
+
+    /// Creates a [`VerifierConfiguration`] for `proof`, taking the layout from the proof's public
+    /// input and the hasher and Stone version from the enabled cargo features.
+    ///
+    /// Fails if more than one hasher or Stone version is enabled, in which case the configuration
+    /// must be built with [`VerifierConfiguration::new`] instead.
+    pub fn from_proof(
+        proof: &StarkProof,
+        memory_verification: MemoryVerification,
+    ) -> Result<Self, ConfigError> {
+        Ok(Self::new(
+            Layout::from_felt(proof.public_input.layout)?,
+            Hasher::only_enabled()?,
+            StoneVersion::only_enabled()?,
+            memory_verification,
+        ))
+    }
+
+    /// Parses the layout, failing if it's unknown.
+    pub fn layout(&self) -> Result<Layout, ConfigError> {
+        Layout::from_felt(self.layout)
+    }
+
+    /// Parses the hasher, failing if it's unknown.
+    pub fn hasher(&self) -> Result<Hasher, ConfigError> {
+        Hasher::from_felt(self.hasher)
+    }
+
+    /// Parses the Stone version, failing if it's unknown.
+    pub fn stone_version(&self) -> Result<StoneVersion, ConfigError> {
+        StoneVersion::from_felt(self.stone_version)
+    }
+
+    /// Parses the memory verification mode, failing if it's unknown.
+    pub fn memory_verification(&self) -> Result<MemoryVerification, ConfigError> {
+        MemoryVerification::from_felt(self.memory_verification)
+    }
+
+    /// Checks that every field is known, and that the configuration agrees with `proof`.
+    ///
+    /// Support for the hasher and Stone version doesn't need to be compiled in, as they're only
+    /// needed to split the proof.
+    pub fn validate(&self, proof: &StarkProof) -> Result<(), ConfigError> {
+        self.layout()?;
+        self.hasher()?;
+        self.stone_version()?;
+        self.memory_verification()?;
+
+        if self.layout != proof.public_input.layout {
+            return Err(ConfigError::Mismatch {
+                kind: "layout",
+                config: felt_to_string(self.layout),
+                proof: felt_to_string(proof.public_input.layout),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders a felt as a short string if possible, falling back to hex.
+fn felt_to_string(felt: Felt) -> String {
+    match parse_cairo_short_string(&felt) {
+        Ok(value) if !value.is_empty() && value.chars().all(|c| c.is_ascii_graphic()) => value,
+        _ => format!("{:#x}", felt),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(all(feature = "recursive", feature = "keccak_160_lsb", feature = "stone5"))]
+    use crate::fixtures;
+
+    #[test]
+    fn parses_variants_regardless_of_features() {
+        for hasher in Hasher::ALL {
+            assert_eq!(Hasher::from_felt(hasher.to_felt()).unwrap(), *hasher);
+            assert_eq!(hasher.name().parse::<Hasher>().unwrap(), *hasher);
+        }
+        for stone_version in StoneVersion::ALL {
+            assert_eq!(
+                StoneVersion::from_felt(stone_version.to_felt()).unwrap(),
+                *stone_version
+            );
+        }
+
+        assert!(matches!(
+            "sha256".parse::<Hasher>(),
+            Err(ConfigError::Unknown { kind: "hasher", .. })
+        ));
+    }
+
+    #[cfg(all(feature = "recursive", feature = "keccak_160_lsb", feature = "stone5"))]
+    #[test]
+    fn split_rejects_disabled_hasher() {
+        let Some(hasher) = Hasher::ALL.iter().find(|hasher| !hasher.is_enabled()) else {
+            return;
+        };
+        let mut verifier_config = fixtures::verifier_config();
+        verifier_config.hasher = hasher.to_felt();
+
+        verifier_config.validate(&fixtures::proof()).unwrap();
+        assert!(matches!(
+            crate::split_proof_dyn(fixtures::proof(), verifier_config),
+            Err(crate::Error::Config(ConfigError::NotEnabled {
+                kind: "hasher",
+                ..
+            }))
+        ));
+    }
+}
@@ -0,0 +1,454 @@
+use starknet_core::types::{Felt, NonZeroFelt};
+use starknet_crypto::{poseidon_hash, poseidon_hash_many};
+use swiftness_commitment::{
+    table::types::{Commitment as TableCommitment, Decommitment as TableDecommitment},
+    vector::types::Commitment as VectorCommitment,
+};
+
+use crate::config::{ConfigError, Hasher};
+
+/// Montgomery form conversion factor, as table commitments are computed over Montgomery values.
+const MONTGOMERY_R: Felt =
+    Felt::from_hex_unchecked("0x7FFFFFFFFFFFDF0FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFE1");
+
+/// Magic prefix of the proof of work hash input.
+const POW_MAGIC: u64 = 0x0123456789abcded;
+
+/// Errors from decommitting queries against a table commitment.
+#[derive(Debug, thiserror::Error)]
+pub enum DecommitError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("invalid decommitment length: expected {expected}, got {actual}")]
+    DecommitmentLength { expected: usize, actual: usize },
+    #[error("invalid number of columns {0:#x}")]
+    InvalidColumnCount(Felt),
+    #[error(
+        "authentication node {index} for tree node {node:#x} on the path of query {query:#x} is \
+        missing"
+    )]
+    MissingAuthentication {
+        index: usize,
+        node: Felt,
+        query: Felt,
+    },
+    #[error("no query to decommit")]
+    MissingQuery,
+    #[error("commitment mismatch: expected {expected:#x}, computed {actual:#x}")]
+    RootMismatch { expected: Felt, actual: Felt },
+}
+
+/// A query to a vector commitment.
+struct VectorQuery {
+    index: Felt,
+    value: Felt,
+    depth: Felt,
+    /// Index of the first query whose path goes through the node.
+    query: Felt,
+}
+
+/// Computes the full 32-byte digest of `data`.
+pub(crate) fn digest(hasher: Hasher, data: &[u8]) -> Result<[u8; 32], ConfigError> {
+    let hasher = hasher.ensure_enabled()?;
+
+    match hasher {
+        #[cfg(any(feature = "keccak_160_lsb", feature = "keccak_248_lsb"))]
+        Hasher::Keccak160Lsb | Hasher::Keccak248Lsb => {
+            Ok(<sha3::Keccak256 as sha3::Digest>::digest(data).into())
+        }
+        #[cfg(any(feature = "blake2s_160_lsb", feature = "blake2s_248_lsb"))]
+        Hasher::Blake2s160Lsb | Hasher::Blake2s248Lsb => {
+            Ok(<blake2::Blake2s256 as blake2::Digest>::digest(data).into())
+        }
+        #[allow(unreachable_patterns)]
+        _ => {
+            let _ = data;
+            Err(ConfigError::NotEnabled {
+                kind: "hasher",
+                name: hasher.name(),
+            })
+        }
+    }
+}
+
+/// Hashes `data` into a felt by taking the least significant bits of the digest.
+pub(crate) fn hash_to_felt(hasher: Hasher, data: &[u8]) -> Result<Felt, ConfigError> {
+    let digest = digest(hasher, data)?;
+
+    Ok(match hasher {
+        Hasher::Keccak160Lsb | Hasher::Blake2s160Lsb => Felt::from_bytes_be_slice(&digest[12..]),
+        Hasher::Keccak248Lsb | Hasher::Blake2s248Lsb => Felt::from_bytes_be_slice(&digest[1..]),
+    })
+}
+
+/// Checks the proof of work nonce against the transcript digest, returning whether it's valid.
+pub(crate) fn verify_pow(
+    hasher: Hasher,
+    digest_felt: Felt,
+    n_bits: u8,
+    nonce: u64,
+) -> Result<bool, ConfigError> {
+    // Hash(0x0123456789abcded || digest || n_bits)
+    let mut init_data = Vec::with_capacity(41);
+    init_data.extend_from_slice(&POW_MAGIC.to_be_bytes());
+    init_data.extend_from_slice(&digest_felt.to_bytes_be());
+    init_data.push(n_bits);
+    let init_hash = digest(hasher, &init_data)?;
+
+    // Hash(init_hash || nonce)
+    let mut hash_data = Vec::with_capacity(40);
+    hash_data.extend_from_slice(&init_hash);
+    hash_data.extend_from_slice(&nonce.to_be_bytes());
+    let final_hash = digest(hasher, &hash_data)?;
+
+    let Some(threshold_bits) = 128u8.checked_sub(n_bits) else {
+        return Ok(false);
+    };
+    Ok(Felt::from_bytes_be_slice(&final_hash[0..16]) < Felt::TWO.pow(threshold_bits))
+}
+
+/// Verifies a table decommitment against its commitment.
+pub(crate) fn table_decommit(
+    hasher: Hasher,
+    commitment: &TableCommitment,
+    queries: &[Felt],
+    decommitment: &TableDecommitment,
+    authentications: &[Felt],
+) -> Result<(), DecommitError> {
+    // An extra layer is added to the height since the table is considered as a layer, which is
+    // not included in the vector commitment config.
+    let bottom_layer_depth = commitment.vector_commitment.config.height + Felt::ONE;
+    let is_bottom_layer_verifier_friendly = commitment
+        .vector_commitment
+        .config
+        .n_verifier_friendly_commitment_layers
+        >= bottom_layer_depth;
+
+    let n_columns = u32::try_from(commitment.config.n_columns)
+        .ok()
+        .filter(|n_columns| *n_columns > 0)
+        .ok_or(DecommitError::InvalidColumnCount(
+            commitment.config.n_columns,
+        ))? as usize;
+    let expected_len = n_columns * queries.len();
+    if decommitment.values.len() != expected_len {
+        return Err(DecommitError::DecommitmentLength {
+            expected: expected_len,
+            actual: decommitment.values.len(),
+        });
+    }
+
+    let vector_queries = queries
+        .iter()
+        .zip(decommitment.values.chunks(n_columns))
+        .map(|(index, row)| {
+            let row = row
+                .iter()
+                .map(|value| value * MONTGOMERY_R)
+                .collect::<Vec<_>>();
+
+            let value = if n_columns == 1 {
+                row[0]
+            } else if is_bottom_layer_verifier_friendly {
+                poseidon_hash_many(&row)
+            } else {
+                hash_to_felt(
+                    hasher,
+                    &row.iter()
+                        .flat_map(|value| value.to_bytes_be())
+                        .collect::<Vec<_>>(),
+                )?
+            };
+
+            Ok((*index, value))
+        })
+        .collect::<Result<Vec<_>, ConfigError>>()?;
+
+    vector_commitment_decommit(
+        hasher,
+        &commitment.vector_commitment,
+        &vector_queries,
+        authentications,
+    )
+}
+
+/// Verifies `(index, value)` queries to a vector commitment by recomputing the Merkle root.
+pub(crate) fn vector_commitment_decommit(
+    hasher: Hasher,
+    commitment: &VectorCommitment,
+    queries: &[(Felt, Felt)],
+    authentications: &[Felt],
+) -> Result<(), DecommitError> {
+    let height = commitment.config.height;
+    let n_verifier_friendly_layers = commitment.config.n_verifier_friendly_commitment_layers;
+
+    // Shifts the query indices by 2**height, to convert index representation to heap-like.
+    let shift = Felt::TWO.pow_felt(&height);
+    let mut queue = queries
+        .iter()
+        .map(|(index, value)| VectorQuery {
+            index: index + shift,
+            value: *value,
+            depth: height,
+            query: *index,
+        })
+        .collect::<Vec<_>>();
+
+    let mut start = 0;
+    let mut auth_index = 0;
+    let root = loop {
+        let current = queue.get(start).ok_or(DecommitError::MissingQuery)?;
+        if current.index == Felt::ONE {
+            break current.value;
+        }
+
+        let (parent, bit) = current.index.div_rem(&NonZeroFelt::TWO);
+        let is_verifier_friendly = n_verifier_friendly_layers >= current.depth;
+        let depth = current.depth - Felt::ONE;
+
+        let sibling = queue
+            .get(start + 1)
+            .filter(|next| bit == Felt::ZERO && current.index + Felt::ONE == next.index);
+        let (hash, consumed) = match sibling {
+            Some(next) => (
+                hash_node(hasher, current.value, next.value, is_verifier_friendly)?,
+                2,
+            ),
+            None => {
+                let authentication = *authentications.get(auth_index).ok_or(
+                    DecommitError::MissingAuthentication {
+                        index: auth_index,
+                        node: if bit == Felt::ZERO {
+                            current.index + Felt::ONE
+                        } else {
+                            current.index - Felt::ONE
+                        },
+                        query: current.query,
+                    },
+                )?;
+                auth_index += 1;
+
+                let hash = if bit == Felt::ZERO {
+                    hash_node(hasher, current.value, authentication, is_verifier_friendly)?
+                } else {
+                    hash_node(hasher, authentication, current.value, is_verifier_friendly)?
+                };
+                (hash, 1)
+            }
+        };
+
+        let query = current.query;
+        queue.push(VectorQuery {
+            index: parent,
+            value: hash,
+            depth,
+            query,
+        });
+        start += consumed;
+    };
+
+    if root == commitment.commitment_hash {
+        Ok(())
+    } else {
+        Err(DecommitError::RootMismatch {
+            expected: commitment.commitment_hash,
+            actual: root,
+        })
+    }
+}
+
+fn hash_node(
+    hasher: Hasher,
+    left: Felt,
+    right: Felt,
+    is_verifier_friendly: bool,
+) -> Result<Felt, ConfigError> {
+    if is_verifier_friendly {
+        Ok(poseidon_hash(left, right))
+    } else {
+        let mut data = Vec::with_capacity(64);
+        data.extend_from_slice(&left.to_bytes_be());
+        data.extend_from_slice(&right.to_bytes_be());
+        hash_to_felt(hasher, &data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Published Keccak-256 and BLAKE2s-256 digests of the empty string and of `abc`.
+    #[cfg(any(feature = "keccak_160_lsb", feature = "keccak_248_lsb"))]
+    const KECCAK_256_EMPTY: &str =
+        "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470";
+    #[cfg(any(feature = "keccak_160_lsb", feature = "keccak_248_lsb"))]
+    const KECCAK_256_ABC: &str = "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45";
+    #[cfg(any(feature = "blake2s_160_lsb", feature = "blake2s_248_lsb"))]
+    const BLAKE2S_256_EMPTY: &str =
+        "69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9";
+    #[cfg(any(feature = "blake2s_160_lsb", feature = "blake2s_248_lsb"))]
+    const BLAKE2S_256_ABC: &str =
+        "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982";
+
+    /// Checks the digests of known inputs, and that the felt keeps only the least significant bits.
+    #[cfg(any(
+        feature = "keccak_160_lsb",
+        feature = "keccak_248_lsb",
+        feature = "blake2s_160_lsb",
+        feature = "blake2s_248_lsb"
+    ))]
+    fn check_hasher(hasher: Hasher, digests: [&str; 2], felt_bytes: usize) {
+        for (data, expected) in [b"".as_slice(), b"abc"].into_iter().zip(digests) {
+            let digest = digest(hasher, data).unwrap();
+            assert_eq!(
+                digest
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect::<String>(),
+                expected
+            );
+            assert_eq!(
+                hash_to_felt(hasher, data).unwrap(),
+                Felt::from_hex(&expected[64 - 2 * felt_bytes..]).unwrap()
+            );
+        }
+    }
+
+    #[cfg(feature = "keccak_160_lsb")]
+    #[test]
+    fn keccak_160_lsb_known_answers() {
+        check_hasher(Hasher::Keccak160Lsb, [KECCAK_256_EMPTY, KECCAK_256_ABC], 20);
+        assert_eq!(
+            hash_to_felt(Hasher::Keccak160Lsb, b"").unwrap(),
+            Felt::from_hex_unchecked("0xdcc703c0e500b653ca82273b7bfad8045d85a470")
+        );
+    }
+
+    #[cfg(feature = "keccak_248_lsb")]
+    #[test]
+    fn keccak_248_lsb_known_answers() {
+        check_hasher(Hasher::Keccak248Lsb, [KECCAK_256_EMPTY, KECCAK_256_ABC], 31);
+        assert_eq!(
+            hash_to_felt(Hasher::Keccak248Lsb, b"").unwrap(),
+            Felt::from_hex_unchecked(
+                "0xd2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+            )
+        );
+    }
+
+    #[cfg(feature = "blake2s_160_lsb")]
+    #[test]
+    fn blake2s_160_lsb_known_answers() {
+        check_hasher(
+            Hasher::Blake2s160Lsb,
+            [BLAKE2S_256_EMPTY, BLAKE2S_256_ABC],
+            20,
+        );
+        assert_eq!(
+            hash_to_felt(Hasher::Blake2s160Lsb, b"").unwrap(),
+            Felt::from_hex_unchecked("0x42354a7c1f55b6482ca1a51e1b250dfd1ed0eef9")
+        );
+    }
+
+    #[cfg(feature = "blake2s_248_lsb")]
+    #[test]
+    fn blake2s_248_lsb_known_answers() {
+        check_hasher(
+            Hasher::Blake2s248Lsb,
+            [BLAKE2S_256_EMPTY, BLAKE2S_256_ABC],
+            31,
+        );
+        assert_eq!(
+            hash_to_felt(Hasher::Blake2s248Lsb, b"").unwrap(),
+            Felt::from_hex_unchecked(
+                "0x217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9"
+            )
+        );
+    }
+
+    #[cfg(feature = "keccak_160_lsb")]
+    #[test]
+    fn pow_matches_swiftness() {
+        for digest_felt in [Felt::ZERO, Felt::from(0x1234u32), Felt::MAX] {
+            for n_bits in [0, 4, 8] {
+                for nonce in 0..64 {
+                    assert_eq!(
+                        verify_pow(Hasher::Keccak160Lsb, digest_felt, n_bits, nonce).unwrap(),
+                        swiftness_pow::pow::verify_pow(digest_felt.to_bytes_be(), n_bits, nonce)
+                            .is_ok(),
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(all(feature = "recursive", feature = "keccak_160_lsb", feature = "stone5"))]
+    #[test]
+    fn table_decommit_matches_swiftness_on_fixture() {
+        use swiftness_commitment::{
+            table::{decommit::table_decommit as swiftness_table_decommit, types::Witness},
+            vector::types::Witness as VectorWitness,
+        };
+        use swiftness_fri::{
+            group::get_fri_group,
+            layer::{compute_next_layer, FriLayerComputationParams},
+        };
+
+        use crate::{
+            fixtures,
+            fri::{to_swiftness_query, to_swiftness_table_commitment},
+        };
+
+        let calls = fixtures::calls(Felt::ONE);
+        for (layer, step) in calls.intermediate_steps.iter().enumerate() {
+            let state_constant = &step.state_constant;
+            let (_, indices, values) = compute_next_layer(
+                &mut step
+                    .state_variable
+                    .queries
+                    .iter()
+                    .map(to_swiftness_query)
+                    .collect(),
+                &mut step.witness.leaves.clone(),
+                FriLayerComputationParams {
+                    coset_size: Felt::TWO.pow_felt(&state_constant.step_sizes[layer]),
+                    fri_group: get_fri_group(),
+                    eval_point: state_constant.eval_points[layer],
+                },
+            )
+            .unwrap();
+            let commitment = to_swiftness_table_commitment(&state_constant.commitment[layer]);
+
+            let mut authentications = step.witness.table_witness.vector.authentications.clone();
+            for tampered in [false, true] {
+                if tampered {
+                    authentications[0] += Felt::ONE;
+                }
+
+                let ours = table_decommit(
+                    Hasher::Keccak160Lsb,
+                    &commitment,
+                    &indices,
+                    &TableDecommitment {
+                        values: values.clone(),
+                    },
+                    &authentications,
+                );
+                let theirs = swiftness_table_decommit(
+                    commitment.clone(),
+                    &indices,
+                    TableDecommitment {
+                        values: values.clone(),
+                    },
+                    Witness {
+                        vector: VectorWitness {
+                            authentications: authentications.clone(),
+                        },
+                    },
+                );
+                assert_eq!(ours.is_ok(), !tampered);
+                assert_eq!(theirs.is_ok(), !tampered);
+            }
+        }
+    }
+}
@@ -0,0 +1,405 @@
+use std::{convert::Infallible, fmt::Display, str::FromStr};
+
+use starknet_core::{chain_id, types::Felt};
+
+use crate::{
+    bindings::VerifierConfiguration,
+    pack::{StepEstimator, TransactionLimits, TransactionPacker},
+};
+
+/// Address of the `integrity` fact registry on Starknet mainnet.
+const MAINNET_FACT_REGISTRY: Felt =
+    Felt::from_hex_unchecked("0x00cc63a1e8e7824642b89fa6baf996b8ed21fa4707be90ef7605570ca8e4f00b");
+
+/// Address of the `integrity` fact registry on Starknet Sepolia.
+const SEPOLIA_FACT_REGISTRY: Felt =
+    Felt::from_hex_unchecked("0x04ce7851f00b6c3289674841fd7a1b96b6fd41ed1edc248faccd672c26371b8c");
+
+/// Limits enforced on `INVOKE` transactions by public Starknet networks.
+const STARKNET_LIMITS: TransactionLimits = TransactionLimits {
+    max_calldata_len: 4000,
+    max_steps: 10_000_000,
+};
+
+/// A network the `integrity` contracts are deployed on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Network {
+    /// Starknet mainnet.
+    Mainnet,
+    /// Starknet Sepolia testnet.
+    Sepolia,
+    /// Any other network, such as an appchain, identified by name.
+    Custom(String),
+}
+
+/// A deployment of the `integrity` contracts on a network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deployment {
+    /// The network the contracts are deployed on.
+    pub network: Network,
+    /// Chain ID of the network.
+    pub chain_id: Felt,
+    /// Address of the fact registry, which verification calls are sent to.
+    pub fact_registry: Felt,
+    /// Verifier contracts registered with the fact registry, if known.
+    ///
+    /// Verification calls are sent to the fact registry, which dispatches them to the verifier
+    /// itself, so these are only needed to look verifiers up.
+    pub verifiers: Vec<VerifierDeployment>,
+    /// Limits transactions on the network must stay within.
+    pub limits: TransactionLimits,
+}
+
+/// A verifier contract handling a single [`VerifierConfiguration`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifierDeployment {
+    /// The configuration the verifier handles.
+    pub verifier_config: VerifierConfiguration,
+    /// Address of the verifier contract.
+    pub address: Felt,
+}
+
+/// A set of known `integrity` deployments, at most one per network.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeploymentRegistry {
+    deployments: Vec<Deployment>,
+}
+
+/// Errors from loading custom deployments into a [`DeploymentRegistry`].
+#[derive(Debug, thiserror::Error)]
+pub enum RegistryError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[cfg(feature = "toml")]
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+    #[error("`{0}` is not a valid Cairo short string")]
+    InvalidShortString(String),
+    #[error("network `{0}` is defined more than once")]
+    DuplicateNetwork(Network),
+}
+
+/// Custom deployments as written in TOML files.
+#[cfg(feature = "toml")]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DeploymentsFile {
+    #[serde(default, rename = "deployment")]
+    deployments: Vec<DeploymentEntry>,
+}
+
+#[cfg(feature = "toml")]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DeploymentEntry {
+    network: String,
+    /// Chain ID as a Cairo short string, e.g. `SN_MAIN`.
+    chain_id: String,
+    fact_registry: Felt,
+    max_calldata_len: usize,
+    max_steps: u64,
+    #[serde(default, rename = "verifier")]
+    verifiers: Vec<VerifierEntry>,
+}
+
+/// Verifier configuration fields are Cairo short strings, e.g. `recursive` or `keccak_160_lsb`.
+#[cfg(feature = "toml")]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct VerifierEntry {
+    layout: String,
+    hasher: String,
+    stone_version: String,
+    memory_verification: String,
+    address: Felt,
+}
+
+impl Network {
+    /// Name of the network, as used in deployment files.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Mainnet => "mainnet",
+            Self::Sepolia => "sepolia",
+            Self::Custom(name) => name,
+        }
+    }
+}
+
+impl Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Network {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "mainnet" => Self::Mainnet,
+            "sepolia" => Self::Sepolia,
+            _ => Self::Custom(s.to_owned()),
+        })
+    }
+}
+
+impl Deployment {
+    /// Gets the address of the verifier handling `verifier_config`, if known.
+    pub fn verifier(&self, verifier_config: &VerifierConfiguration) -> Option<Felt> {
+        self.verifiers
+            .iter()
+            .find(|verifier| verifier.verifier_config == *verifier_config)
+            .map(|verifier| verifier.address)
+    }
+
+    /// Creates a [`TransactionPacker`] using the limits of the network.
+    pub fn packer<E>(&self, estimator: E) -> TransactionPacker<E>
+    where
+        E: StepEstimator,
+    {
+        TransactionPacker::new(self.limits, estimator)
+    }
+}
+
+impl DeploymentRegistry {
+    /// Creates an empty [`DeploymentRegistry`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a [`DeploymentRegistry`] with the public deployments on Starknet mainnet and
+    /// Sepolia.
+    ///
+    /// Only the fact registries are included: their verifiers are intentionally left out, as they
+    /// change whenever new configurations are registered. Verifiers can be added with
+    /// [`DeploymentRegistry::insert`] or, with the `toml` feature, loaded from a file.
+    pub fn known() -> Self {
+        Self {
+            deployments: vec![
+                Deployment {
+                    network: Network::Mainnet,
+                    chain_id: chain_id::MAINNET,
+                    fact_registry: MAINNET_FACT_REGISTRY,
+                    verifiers: vec![],
+                    limits: STARKNET_LIMITS,
+                },
+                Deployment {
+                    network: Network::Sepolia,
+                    chain_id: chain_id::SEPOLIA,
+                    fact_registry: SEPOLIA_FACT_REGISTRY,
+                    verifiers: vec![],
+                    limits: STARKNET_LIMITS,
+                },
+            ],
+        }
+    }
+
+    /// Gets all deployments in the registry.
+    pub fn deployments(&self) -> &[Deployment] {
+        &self.deployments
+    }
+
+    /// Gets the deployment on `network`.
+    pub fn get(&self, network: &Network) -> Option<&Deployment> {
+        self.deployments
+            .iter()
+            .find(|deployment| deployment.network == *network)
+    }
+
+    /// Gets the deployment on the network identified by `chain_id`.
+    pub fn get_by_chain_id(&self, chain_id: Felt) -> Option<&Deployment> {
+        self.deployments
+            .iter()
+            .find(|deployment| deployment.chain_id == chain_id)
+    }
+
+    /// Adds a deployment, returning the one it replaces on the same network, if any.
+    pub fn insert(&mut self, deployment: Deployment) -> Option<Deployment> {
+        match self
+            .deployments
+            .iter_mut()
+            .find(|existing| existing.network == deployment.network)
+        {
+            Some(existing) => Some(std::mem::replace(existing, deployment)),
+            None => {
+                self.deployments.push(deployment);
+                None
+            }
+        }
+    }
+
+    /// Adds the deployments defined in the TOML file at `path`, replacing existing ones on the
+    /// same networks.
+    #[cfg(feature = "toml")]
+    pub fn load_toml<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<(), RegistryError> {
+        self.load_toml_str(&std::fs::read_to_string(path)?)
+    }
+
+    /// Adds the deployments defined in a TOML document, replacing existing ones on the same
+    /// networks.
+    ///
+    /// Each deployment is a `[[deployment]]` table with `network`, `chain_id`, `fact_registry`,
+    /// `max_calldata_len` and `max_steps` keys, and optionally `[[deployment.verifier]]` tables
+    /// with `layout`, `hasher`, `stone_version`, `memory_verification` and `address` keys.
+    #[cfg(feature = "toml")]
+    pub fn load_toml_str(&mut self, content: &str) -> Result<(), RegistryError> {
+        let file: DeploymentsFile = toml::from_str(content)?;
+
+        // Parsed in full before inserting anything so that a bad file leaves the registry as is.
+        let mut deployments: Vec<Deployment> = vec![];
+        for entry in file.deployments {
+            let deployment = entry.into_deployment()?;
+            if deployments
+                .iter()
+                .any(|existing| existing.network == deployment.network)
+            {
+                return Err(RegistryError::DuplicateNetwork(deployment.network));
+            }
+            deployments.push(deployment);
+        }
+
+        for deployment in deployments {
+            self.insert(deployment);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "toml")]
+impl DeploymentEntry {
+    fn into_deployment(self) -> Result<Deployment, RegistryError> {
+        Ok(Deployment {
+            network: Network::from_str(&self.network).unwrap_or_else(|never| match never {}),
+            chain_id: short_string(&self.chain_id)?,
+            fact_registry: self.fact_registry,
+            verifiers: self
+                .verifiers
+                .into_iter()
+                .map(|verifier| {
+                    Ok(VerifierDeployment {
+                        verifier_config: VerifierConfiguration {
+                            layout: short_string(&verifier.layout)?,
+                            hasher: short_string(&verifier.hasher)?,
+                            stone_version: short_string(&verifier.stone_version)?,
+                            memory_verification: short_string(&verifier.memory_verification)?,
+                        },
+                        address: verifier.address,
+                    })
+                })
+                .collect::<Result<_, RegistryError>>()?,
+            limits: TransactionLimits {
+                max_calldata_len: self.max_calldata_len,
+                max_steps: self.max_steps,
+            },
+        })
+    }
+}
+
+#[cfg(feature = "toml")]
+fn short_string(value: &str) -> Result<Felt, RegistryError> {
+    starknet_core::utils::cairo_short_string_to_felt(value)
+        .map_err(|_| RegistryError::InvalidShortString(value.to_owned()))
+}
+
+#[cfg(all(test, feature = "toml"))]
+mod tests {
+    use super::*;
+
+    use crate::config::{Hasher, Layout, MemoryVerification, StoneVersion};
+
+    const APPCHAIN: &str = r#"
+        [[deployment]]
+        network = "appchain"
+        chain_id = "MY_APPCHAIN"
+        fact_registry = "0x1234"
+        max_calldata_len = 5000
+        max_steps = 20000000
+
+        [[deployment.verifier]]
+        layout = "recursive"
+        hasher = "keccak_160_lsb"
+        stone_version = "stone5"
+        memory_verification = "strict"
+        address = "0x5678"
+    "#;
+
+    const SEPOLIA: &str = r#"
+        [[deployment]]
+        network = "sepolia"
+        chain_id = "SN_SEPOLIA"
+        fact_registry = "0x9abc"
+        max_calldata_len = 4000
+        max_steps = 10000000
+    "#;
+
+    #[test]
+    fn loads_toml_deployments() {
+        let mut registry = DeploymentRegistry::known();
+        registry
+            .load_toml_str(&format!("{APPCHAIN}{SEPOLIA}"))
+            .unwrap();
+
+        let network = Network::Custom("appchain".into());
+        let appchain = registry.get(&network).unwrap();
+        assert_eq!(
+            registry.get_by_chain_id(appchain.chain_id).unwrap().network,
+            network
+        );
+        assert_eq!(appchain.fact_registry, Felt::from(0x1234));
+        assert_eq!(
+            appchain.limits,
+            TransactionLimits {
+                max_calldata_len: 5000,
+                max_steps: 20_000_000,
+            }
+        );
+        let verifier_config = VerifierConfiguration::new(
+            Layout::Recursive,
+            Hasher::Keccak160Lsb,
+            StoneVersion::Stone5,
+            MemoryVerification::Strict,
+        );
+        assert_eq!(
+            appchain.verifier(&verifier_config),
+            Some(Felt::from(0x5678))
+        );
+
+        // Existing deployments on the same network are replaced, others are kept.
+        assert_eq!(
+            registry.get(&Network::Sepolia).unwrap().fact_registry,
+            Felt::from(0x9abc)
+        );
+        assert_eq!(
+            registry.get(&Network::Mainnet).unwrap().fact_registry,
+            MAINNET_FACT_REGISTRY
+        );
+        assert_eq!(registry.deployments().len(), 3);
+    }
+
+    #[test]
+    fn rejects_duplicate_network() {
+        let content = format!("{APPCHAIN}{SEPOLIA}{SEPOLIA}");
+        let mut registry = DeploymentRegistry::new();
+
+        assert!(matches!(
+            registry.load_toml_str(&content),
+            Err(RegistryError::DuplicateNetwork(Network::Sepolia))
+        ));
+        // Nothing is inserted from a bad file.
+        assert!(registry.deployments().is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_short_string() {
+        let content = APPCHAIN.replace("MY_APPCHAIN", "A_CHAIN_ID_LONGER_THAN_31_CHARACTERS");
+        let mut registry = DeploymentRegistry::new();
+
+        assert!(matches!(
+            registry.load_toml_str(&content),
+            Err(RegistryError::InvalidShortString(value))
+                if value == "A_CHAIN_ID_LONGER_THAN_31_CHARACTERS"
+        ));
+        assert!(registry.deployments().is_empty());
+    }
+}
@@ -0,0 +1,95 @@
+use starknet_core::types::Felt;
+use swiftness::commit::Error as CommitError;
+use swiftness_air::layout::PublicInputError;
+use swiftness_fri::layer::FriError;
+use swiftness_stark::config::Error as StarkConfigError;
+
+use crate::{
+    commit::StarkCommitError, config::ConfigError, crypto::DecommitError, fact::FactError,
+    pack::PackError, preflight::PreflightError,
+};
+
+// `swiftness` errors are built without `std`, so they can't be chained as a `#[source]`.
+
+/// Errors from splitting a proof and turning it into contract calls.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Fact(#[from] FactError),
+    #[error(transparent)]
+    Pack(#[from] PackError),
+    #[error("the layout does not define the number of {trace} trace columns for this proof")]
+    ColumnMissing { trace: &'static str },
+    #[error("invalid FRI config: {n_layers:#x} layers is outside {min}..={max}")]
+    FriLayerCountOutOfRange { n_layers: Felt, min: u64, max: u64 },
+    #[error("invalid FRI config: the first step size is {0:#x} instead of 0")]
+    FirstFriStepNotZero(Felt),
+    #[error(
+        "invalid FRI config: step size {step_size:#x} of layer {layer} is outside {min}..={max}"
+    )]
+    FriStepSizeOutOfRange {
+        layer: usize,
+        step_size: Felt,
+        min: u64,
+        max: u64,
+    },
+    #[error("`{field}` has {actual} elements instead of {expected}")]
+    LengthMismatch {
+        field: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("`{field}` is {value:#x}, exceeding the limit of {max:#x}")]
+    ValueTooLarge {
+        field: &'static str,
+        value: Felt,
+        max: Felt,
+    },
+    #[error("`{field}` value {value:#x} does not fit in the target integer type")]
+    Overflow { field: &'static str, value: Felt },
+    #[error("FRI layer {layer} expects {expected} leaves, got {actual}")]
+    LeafCountMismatch {
+        layer: usize,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("FRI layer {layer} computation failed: {error}")]
+    LayerComputation { layer: usize, error: FriError },
+    #[error("all verification steps have already been generated")]
+    StepsExhausted,
+    #[error("cannot resume from FRI layer {layer}: expected a layer in {next_layer}..={n_layers}")]
+    ResumeLayerOutOfRange {
+        layer: usize,
+        next_layer: usize,
+        n_layers: usize,
+    },
+    #[error("FRI state doesn't match layer {layer} of the proof from query {query} on")]
+    ResumeStateMismatch { layer: usize, query: usize },
+    #[error("a previous step failed to be generated")]
+    PreviousStepFailed,
+    #[error("STARK commitment failed: {0}")]
+    Commit(CommitError),
+    #[error("invalid STARK config: {0}")]
+    InvalidConfig(StarkConfigError),
+    #[error("invalid public input: {0}")]
+    InvalidPublicInput(PublicInputError),
+    #[error("{table} decommitment failed: {error}")]
+    Decommitment {
+        table: &'static str,
+        #[source]
+        error: DecommitError,
+    },
+    #[error("FRI verification failed: {0}")]
+    Preflight(Box<PreflightError>),
+}
+
+impl From<StarkCommitError> for Error {
+    fn from(value: StarkCommitError) -> Self {
+        match value {
+            StarkCommitError::Config(err) => Self::Config(err),
+            StarkCommitError::Commit(err) => Self::Commit(err),
+        }
+    }
+}
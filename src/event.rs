@@ -0,0 +1,229 @@
+use starknet_core::{
+    codec::{Decode, Encode, Error as CodecError},
+    types::{Event, Felt},
+};
+
+use crate::{
+    bindings::VerifierConfiguration,
+    fact::{verification_hash, ProofFact, ProofVerification},
+};
+
+/// Event key for `FactRegistered`.
+const SELECTOR_FACT_REGISTERED_EVENT: Felt = Felt::from_raw([
+    306531817757065129,
+    11411360851580319436,
+    15811258278132572179,
+    15698657718856048762,
+]);
+
+/// The `FactRegistered` event emitted by the `integrity` fact registry once a proof is verified.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[starknet(core = "starknet_core")]
+pub struct FactRegistered {
+    pub fact_hash: Felt,
+    pub verifier_address: Felt,
+    pub security_bits: u32,
+    pub verifier_config: VerifierConfiguration,
+    pub verification_hash: Felt,
+}
+
+/// Errors from decoding `integrity` contract events.
+#[derive(Debug, thiserror::Error)]
+pub enum EventError {
+    #[error("failed to decode `FactRegistered` event: {0}")]
+    Decode(#[from] CodecError),
+    #[error("`FactRegistered` event has {count} trailing felts")]
+    TrailingFelts { count: usize },
+}
+
+impl FactRegistered {
+    /// Decodes `event` as a `FactRegistered` event, returning `None` if it's a different event.
+    pub fn decode_event(event: &Event) -> Result<Option<Self>, EventError> {
+        let Some((selector, keys)) = event.keys.split_first() else {
+            return Ok(None);
+        };
+        if *selector != SELECTOR_FACT_REGISTERED_EVENT {
+            return Ok(None);
+        }
+
+        // All members are currently emitted as keys, but members moved to data in any contract
+        // version are picked up as well.
+        let mut iter = keys.iter().chain(event.data.iter());
+        let decoded = Self::decode_iter(&mut iter)?;
+
+        let count = iter.count();
+        if count != 0 {
+            return Err(EventError::TrailingFelts { count });
+        }
+
+        Ok(Some(decoded))
+    }
+
+    /// Decodes all `FactRegistered` events emitted by the `integrity` contract at
+    /// `contract_address`, e.g. out of a transaction receipt, skipping any other event.
+    pub fn decode_events(
+        events: &[Event],
+        contract_address: Felt,
+    ) -> Result<Vec<Self>, EventError> {
+        events
+            .iter()
+            .filter(|event| event.from_address == contract_address)
+            .filter_map(|event| Self::decode_event(event).transpose())
+            .collect()
+    }
+
+    /// Checks that the event registers `fact`, and that its verification hash is consistent with
+    /// the rest of the event.
+    pub fn is_for_fact(&self, fact: &ProofFact) -> bool {
+        self.fact_hash == fact.fact_hash
+            && verification_hash(
+                self.fact_hash,
+                &self.verifier_config,
+                self.security_bits.into(),
+            ) == self.verification_hash
+    }
+
+    /// Checks that the event registers exactly `verification`, as computed locally from a proof.
+    pub fn is_for_verification(&self, verification: &ProofVerification) -> bool {
+        self.is_for_fact(&verification.fact)
+            && self.verification_hash == verification.verification_hash
+    }
+}
+
+/// Finds the `FactRegistered` event emitted by the `integrity` contract at `contract_address` for
+/// `fact` among `events`, e.g. out of the receipt of the final verification call.
+pub fn find_fact_registered(
+    events: &[Event],
+    contract_address: Felt,
+    fact: &ProofFact,
+) -> Result<Option<FactRegistered>, EventError> {
+    Ok(FactRegistered::decode_events(events, contract_address)?
+        .into_iter()
+        .find(|event| event.is_for_fact(fact)))
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet_core::utils::get_selector_from_name;
+
+    use super::*;
+    use crate::config::{Hasher, Layout, MemoryVerification, StoneVersion};
+
+    const CONTRACT_ADDRESS: Felt = Felt::from_hex_unchecked("0x1234");
+
+    fn fact() -> ProofFact {
+        ProofFact {
+            program_hash: Felt::ONE,
+            output_hash: Felt::TWO,
+            fact_hash: Felt::THREE,
+        }
+    }
+
+    fn fact_registered() -> FactRegistered {
+        let verifier_config = VerifierConfiguration::new(
+            Layout::Recursive,
+            Hasher::Keccak160Lsb,
+            StoneVersion::Stone5,
+            MemoryVerification::Strict,
+        );
+        let fact = fact();
+        FactRegistered {
+            fact_hash: fact.fact_hash,
+            verifier_address: Felt::from(0x5678u32),
+            security_bits: 60,
+            verification_hash: verification_hash(fact.fact_hash, &verifier_config, 60u32.into()),
+            verifier_config,
+        }
+    }
+
+    fn event(fact_registered: &FactRegistered) -> Event {
+        let mut keys = vec![SELECTOR_FACT_REGISTERED_EVENT];
+        fact_registered.encode(&mut keys).unwrap();
+        Event {
+            from_address: CONTRACT_ADDRESS,
+            keys,
+            data: vec![],
+        }
+    }
+
+    #[test]
+    fn selector_matches_event_name() {
+        assert_eq!(
+            SELECTOR_FACT_REGISTERED_EVENT,
+            get_selector_from_name("FactRegistered").unwrap()
+        );
+    }
+
+    #[test]
+    fn decodes_event() {
+        let fact_registered = fact_registered();
+        assert_eq!(
+            FactRegistered::decode_event(&event(&fact_registered)).unwrap(),
+            Some(fact_registered.clone())
+        );
+
+        // Members moved to data are decoded in order after the keys.
+        let mut moved = event(&fact_registered);
+        moved.data = moved.keys.split_off(3);
+        assert_eq!(
+            FactRegistered::decode_event(&moved).unwrap(),
+            Some(fact_registered)
+        );
+    }
+
+    #[test]
+    fn skips_other_events() {
+        let mut other = event(&fact_registered());
+        other.keys[0] = get_selector_from_name("Transfer").unwrap();
+        assert_eq!(FactRegistered::decode_event(&other).unwrap(), None);
+
+        other.keys.clear();
+        assert_eq!(FactRegistered::decode_event(&other).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_trailing_felts() {
+        let mut event = event(&fact_registered());
+        event.data.extend([Felt::ONE, Felt::TWO]);
+        assert!(matches!(
+            FactRegistered::decode_event(&event),
+            Err(EventError::TrailingFelts { count: 2 })
+        ));
+    }
+
+    #[test]
+    fn filters_events_by_contract() {
+        let fact_registered = fact_registered();
+        let mut other = event(&fact_registered);
+        other.from_address = Felt::from(0x9999u32);
+
+        let events = [other, event(&fact_registered)];
+        assert_eq!(
+            FactRegistered::decode_events(&events, CONTRACT_ADDRESS).unwrap(),
+            std::slice::from_ref(&fact_registered)
+        );
+        assert_eq!(
+            find_fact_registered(&events, CONTRACT_ADDRESS, &fact()).unwrap(),
+            Some(fact_registered)
+        );
+        assert!(
+            FactRegistered::decode_events(&events[..1], CONTRACT_ADDRESS)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn checks_fact_and_verification_hash() {
+        let mut fact_registered = fact_registered();
+        assert!(fact_registered.is_for_fact(&fact()));
+
+        let mut other_fact = fact();
+        other_fact.fact_hash = Felt::from(4u32);
+        assert!(!fact_registered.is_for_fact(&other_fact));
+
+        fact_registered.verification_hash += Felt::ONE;
+        assert!(!fact_registered.is_for_fact(&fact()));
+    }
+}
@@ -0,0 +1,506 @@
+use std::collections::HashMap;
+
+use starknet_core::types::Felt;
+use starknet_crypto::poseidon_hash_many;
+use swiftness::config::StarkConfig;
+use swiftness_air::public_memory::{PublicInput, MAX_ADDRESS};
+use swiftness_stark::types::StarkProof;
+
+use crate::{
+    bindings::{Verification, VerificationListElement, VerifierConfiguration},
+    config::{ConfigError, MemoryVerification},
+};
+
+/// Address of the first instruction, where the program segment must begin.
+const INITIAL_PC: u64 = 1;
+
+const PROGRAM_SEGMENT: usize = 0;
+const EXECUTION_SEGMENT: usize = 1;
+const OUTPUT_SEGMENT: usize = 2;
+
+/// Instruction: `ap += N_BUILTINS`.
+const INSTRUCTION_ADD_AP: Felt = Felt::from_hex_unchecked("0x40780017fff7fff");
+/// Instruction: `call rel ?`.
+const INSTRUCTION_CALL_REL: Felt = Felt::from_hex_unchecked("0x1104800180018000");
+/// Instruction: `jmp rel 0`.
+const INSTRUCTION_JMP_REL: Felt = Felt::from_hex_unchecked("0x10780017fff7fff");
+
+/// The fact a proof attests to, as registered by the `integrity` fact registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProofFact {
+    /// Poseidon hash of the proven program.
+    pub program_hash: Felt,
+    /// Poseidon hash of the program output.
+    pub output_hash: Felt,
+    /// Poseidon hash of `program_hash` and `output_hash`.
+    pub fact_hash: Felt,
+}
+
+/// The verification record the `integrity` fact registry stores for a proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofVerification {
+    /// The fact being verified.
+    pub fact: ProofFact,
+    /// Poseidon hash of the verifier configuration.
+    pub verifier_config_hash: Felt,
+    /// Security bits of the proof.
+    pub security_bits: Felt,
+    /// Poseidon hash of the fact hash, verifier config hash and security bits.
+    pub verification_hash: Felt,
+}
+
+/// Errors from computing the fact of a proof.
+#[derive(Debug, thiserror::Error)]
+pub enum FactError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("public memory segment {index} is missing")]
+    SegmentMissing { index: usize },
+    #[error("address {0:#x} is out of range")]
+    AddressOutOfRange(Felt),
+    #[error("continuous pages are not supported")]
+    ContinuousPagesNotSupported,
+    #[error("invalid initial pc {0:#x}")]
+    InvalidInitialPc(Felt),
+    #[error("invalid final pc {0:#x}")]
+    InvalidFinalPc(Felt),
+    #[error("address {0:#x} is missing from the main page")]
+    MemoryMissing(Felt),
+    #[error("unexpected value {actual:#x} at address {address:#x}, expected {expected:#x}")]
+    MemoryMismatch {
+        address: Felt,
+        expected: Felt,
+        actual: Felt,
+    },
+    #[error("output of {output_len} felts does not fit in a main page of {main_page_len} entries")]
+    OutputTooLong {
+        output_len: usize,
+        main_page_len: usize,
+    },
+}
+
+impl VerifierConfiguration {
+    /// Computes the hash identifying the configuration in the `integrity` fact registry.
+    pub fn hash(&self) -> Felt {
+        poseidon_hash_many(&[
+            self.layout,
+            self.hasher,
+            self.stone_version,
+            self.memory_verification,
+        ])
+    }
+}
+
+impl Verification {
+    /// Computes the hash the `integrity` fact registry identifies the verification with.
+    pub fn verification_hash(&self) -> Felt {
+        verification_hash(
+            self.fact_hash,
+            &self.verifier_config,
+            self.security_bits.into(),
+        )
+    }
+}
+
+impl VerificationListElement {
+    /// Checks that the element was registered for `fact_hash`, by recomputing its verification
+    /// hash.
+    pub fn is_for_fact(&self, fact_hash: Felt) -> bool {
+        verification_hash(fact_hash, &self.verifier_config, self.security_bits.into())
+            == self.verification_hash
+    }
+}
+
+impl ProofVerification {
+    /// Checks whether this verification is among the ones returned by
+    /// `get_all_verifications_for_fact_hash`.
+    pub fn is_registered_in(&self, verifications: &[VerificationListElement]) -> bool {
+        verifications
+            .iter()
+            .any(|element| element.verification_hash == self.verification_hash)
+    }
+}
+
+/// Computes the number of security bits of a proof with the given config.
+pub fn security_bits(config: &StarkConfig) -> Felt {
+    config.n_queries * config.log_n_cosets + Felt::from(config.proof_of_work.n_bits)
+}
+
+/// Computes the hash the `integrity` fact registry identifies a verification with.
+pub fn verification_hash(
+    fact_hash: Felt,
+    verifier_config: &VerifierConfiguration,
+    security_bits: Felt,
+) -> Felt {
+    poseidon_hash_many(&[fact_hash, verifier_config.hash(), security_bits])
+}
+
+/// Computes the program hash, output hash and fact hash from a proof's public input, the same way
+/// the `integrity` verifier does for the given memory verification mode.
+///
+/// In [`MemoryVerification::Strict`] mode, the bootloader checks performed by the verifier are also
+/// performed here.
+pub fn compute_fact(
+    public_input: &PublicInput,
+    memory_verification: MemoryVerification,
+) -> Result<ProofFact, FactError> {
+    let (program, output) = program_and_output(public_input, memory_verification)?;
+
+    let program_hash = poseidon_hash_many(&program);
+    let output_hash = poseidon_hash_many(&output);
+
+    Ok(ProofFact {
+        program_hash,
+        output_hash,
+        fact_hash: poseidon_hash_many(&[program_hash, output_hash]),
+    })
+}
+
+/// Extracts the program and its output from the public memory.
+fn program_and_output(
+    public_input: &PublicInput,
+    memory_verification: MemoryVerification,
+) -> Result<(Vec<Felt>, Vec<Felt>), FactError> {
+    if !public_input.continuous_page_headers.is_empty() {
+        return Err(FactError::ContinuousPagesNotSupported);
+    }
+
+    let segment = |index: usize| {
+        public_input
+            .segments
+            .get(index)
+            .ok_or(FactError::SegmentMissing { index })
+    };
+    let program_segment = segment(PROGRAM_SEGMENT)?;
+    let execution_segment = segment(EXECUTION_SEGMENT)?;
+    let output_segment = segment(OUTPUT_SEGMENT)?;
+
+    let initial_pc = program_segment.begin_addr;
+    for ap in [execution_segment.begin_addr, execution_segment.stop_ptr] {
+        if ap >= MAX_ADDRESS {
+            return Err(FactError::AddressOutOfRange(ap));
+        }
+    }
+    let initial_ap = address(execution_segment.begin_addr)?;
+    let final_ap = address(execution_segment.stop_ptr)?;
+    if initial_pc != Felt::from(INITIAL_PC) {
+        return Err(FactError::InvalidInitialPc(initial_pc));
+    }
+
+    let output_start = address(output_segment.begin_addr)?;
+    let output_len = address(output_segment.stop_ptr)?
+        .checked_sub(output_start)
+        .ok_or(FactError::AddressOutOfRange(output_segment.stop_ptr))?
+        as usize;
+
+    let (program, output) = match memory_verification {
+        MemoryVerification::Strict | MemoryVerification::Relaxed => {
+            let memory = public_input
+                .main_page
+                .iter()
+                .map(|entry| (entry.address, entry.value))
+                .collect::<HashMap<_, _>>();
+            let read = |address: u64| {
+                memory
+                    .get(&Felt::from(address))
+                    .copied()
+                    .ok_or(FactError::MemoryMissing(address.into()))
+            };
+            let read_range = |start: u64, len: u64| {
+                let end = start
+                    .checked_add(len)
+                    .ok_or(FactError::AddressOutOfRange(start.into()))?;
+                (start..end).map(read).collect::<Result<Vec<_>, _>>()
+            };
+
+            // The bootloader is loaded right before the initial frame, which holds the return fp
+            // and pc.
+            let initial_fp = initial_ap;
+            let program_end_pc = initial_fp
+                .checked_sub(2)
+                .filter(|end| *end >= INITIAL_PC)
+                .ok_or(FactError::AddressOutOfRange(execution_segment.begin_addr))?;
+            let program = read_range(INITIAL_PC, program_end_pc - INITIAL_PC)?;
+
+            if memory_verification == MemoryVerification::Strict {
+                verify_bootloader(public_input, &program, initial_fp, final_ap, &read)?;
+            }
+
+            (program, read_range(output_start, output_len as u64)?)
+        }
+        MemoryVerification::Cairo1 => {
+            // The main page holds the program followed by the output.
+            let main_page_len = public_input.main_page.len();
+            let program_len =
+                main_page_len
+                    .checked_sub(output_len)
+                    .ok_or(FactError::OutputTooLong {
+                        output_len,
+                        main_page_len,
+                    })?;
+            let values = public_input
+                .main_page
+                .iter()
+                .map(|entry| entry.value)
+                .collect::<Vec<_>>();
+
+            (
+                values[..program_len].to_vec(),
+                values[program_len..].to_vec(),
+            )
+        }
+    };
+
+    Ok((program, output))
+}
+
+/// Computes the fact and verification hashes the `integrity` fact registry would record for
+/// `proof` when verified with `verifier_config`.
+pub fn compute_verification(
+    proof: &StarkProof,
+    verifier_config: &VerifierConfiguration,
+) -> Result<ProofVerification, FactError> {
+    let fact = compute_fact(&proof.public_input, verifier_config.memory_verification()?)?;
+    let security_bits = security_bits(&proof.config);
+
+    Ok(ProofVerification {
+        fact,
+        verifier_config_hash: verifier_config.hash(),
+        security_bits,
+        verification_hash: verification_hash(fact.fact_hash, verifier_config, security_bits),
+    })
+}
+
+/// Performs the checks that the proven program is the bootloader, which returns right away after
+/// calling `main` with all builtin segments.
+fn verify_bootloader(
+    public_input: &PublicInput,
+    program: &[Felt],
+    initial_fp: u64,
+    final_ap: u64,
+    read: &impl Fn(u64) -> Result<Felt, FactError>,
+) -> Result<(), FactError> {
+    let expect = |address: u64, expected: Felt| {
+        let actual = read(address)?;
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(FactError::MemoryMismatch {
+                address: address.into(),
+                expected,
+                actual,
+            })
+        }
+    };
+
+    // Segments other than program and execution are builtin segments, including output.
+    let builtin_segments = public_input
+        .segments
+        .get(OUTPUT_SEGMENT..)
+        .unwrap_or_default();
+    let n_builtins = builtin_segments.len() as u64;
+
+    let final_pc = public_input.segments[PROGRAM_SEGMENT].stop_ptr;
+    if final_pc != Felt::from(INITIAL_PC + 4) {
+        return Err(FactError::InvalidFinalPc(final_pc));
+    }
+
+    let prologue = [
+        (0, INSTRUCTION_ADD_AP),
+        (1, Felt::from(n_builtins)),
+        (2, INSTRUCTION_CALL_REL),
+        (4, INSTRUCTION_JMP_REL),
+        (5, Felt::ZERO),
+    ];
+    for (offset, expected) in prologue {
+        let actual = program
+            .get(offset)
+            .copied()
+            .ok_or(FactError::MemoryMissing(Felt::from(
+                INITIAL_PC + offset as u64,
+            )))?;
+        if actual != expected {
+            return Err(FactError::MemoryMismatch {
+                address: Felt::from(INITIAL_PC + offset as u64),
+                expected,
+                actual,
+            });
+        }
+    }
+
+    // The initial frame returns to the end of the program with an empty frame pointer.
+    expect(initial_fp - 2, Felt::from(initial_fp))?;
+    expect(initial_fp - 1, Felt::ZERO)?;
+
+    // Builtin pointers are passed to `main` on the stack and returned at the end of execution.
+    let final_stack = final_ap
+        .checked_sub(n_builtins)
+        .ok_or(FactError::AddressOutOfRange(final_ap.into()))?;
+    for (offset, segment) in builtin_segments.iter().enumerate() {
+        expect(initial_fp + offset as u64, segment.begin_addr)?;
+        expect(final_stack + offset as u64, segment.stop_ptr)?;
+    }
+
+    Ok(())
+}
+
+fn address(felt: Felt) -> Result<u64, FactError> {
+    u64::try_from(felt).map_err(|_| FactError::AddressOutOfRange(felt))
+}
+
+#[cfg(all(
+    test,
+    feature = "recursive",
+    feature = "keccak_160_lsb",
+    feature = "stone5"
+))]
+mod tests {
+    use starknet_core::crypto::compute_hash_on_elements;
+
+    use super::*;
+    use crate::fixtures;
+
+    /// Pedersen program hash of the fixture, as published by the `swiftness` proof test.
+    const FIXTURE_PEDERSEN_PROGRAM_HASH: Felt = Felt::from_hex_unchecked(
+        "0x9f6693f4a5610a46b5d71ef573c43bef5f0d111fc1c5e506d509c458a29bae",
+    );
+
+    /// Poseidon hashes of the fixture program in bootloader and Cairo 1 memory layouts, and of its
+    /// output.
+    const PROGRAM_HASH: Felt = Felt::from_hex_unchecked(
+        "0x07ac5582e353f8750487838481a46b5429ef84b2f18f909aaab9388f1fe0a28b",
+    );
+    const CAIRO1_PROGRAM_HASH: Felt = Felt::from_hex_unchecked(
+        "0x051bc88bdfdfb6b76313a7134092e4ddcbfd4bdb221274f0be1d3d6db7d8173b",
+    );
+    const OUTPUT_HASH: Felt = Felt::from_hex_unchecked(
+        "0x060cbf4532b874a9a19557a55b45663831f71e21438525174b82842a1fab0ec4",
+    );
+
+    fn verification(memory_verification: MemoryVerification) -> ProofVerification {
+        let mut verifier_config = fixtures::verifier_config();
+        verifier_config.memory_verification = memory_verification.to_felt();
+        compute_verification(&fixtures::proof(), &verifier_config).unwrap()
+    }
+
+    #[test]
+    fn extracts_program_and_output() {
+        let public_input = fixtures::proof().public_input;
+        for memory_verification in [MemoryVerification::Strict, MemoryVerification::Relaxed] {
+            let (program, output) = program_and_output(&public_input, memory_verification).unwrap();
+            assert_eq!(
+                compute_hash_on_elements(&program),
+                FIXTURE_PEDERSEN_PROGRAM_HASH
+            );
+            assert_eq!(output, [Felt::from(0xa), Felt::from(0x90)]);
+        }
+
+        let (program, output) =
+            program_and_output(&public_input, MemoryVerification::Cairo1).unwrap();
+        assert_eq!(program.len() + output.len(), public_input.main_page.len());
+        assert_eq!(output, [Felt::from(0xa), Felt::from(0x90)]);
+    }
+
+    #[test]
+    fn computes_fixture_facts() {
+        for (memory_verification, program_hash, fact_hash, verification_hash) in [
+            (
+                MemoryVerification::Strict,
+                PROGRAM_HASH,
+                "0x032fc402a33e11316a8be5fbc6094e388bf2804969753715bcbc9b783b1e1156",
+                "0x0571f758be98a1824edaab35f9a3b4eb1f884a3c2479c22bf2250d39bb9851ab",
+            ),
+            (
+                MemoryVerification::Relaxed,
+                PROGRAM_HASH,
+                "0x032fc402a33e11316a8be5fbc6094e388bf2804969753715bcbc9b783b1e1156",
+                "0x061d7a2a40d7227afe66d3819d378dd9dbbcd539d979b562907326fbdb3d3bac",
+            ),
+            (
+                MemoryVerification::Cairo1,
+                CAIRO1_PROGRAM_HASH,
+                "0x04171dc298e51ae334f8ada1a5b8adc1fad903670708b7fb609f426c80ff91b2",
+                "0x0559223dfd6aff31b1451c2c6494cdafcb746687732eb9d39ab951c2ddc14f0b",
+            ),
+        ] {
+            let verification = verification(memory_verification);
+            assert_eq!(
+                verification.fact,
+                ProofFact {
+                    program_hash,
+                    output_hash: OUTPUT_HASH,
+                    fact_hash: Felt::from_hex_unchecked(fact_hash),
+                }
+            );
+            assert_eq!(verification.security_bits, Felt::from(50));
+            assert_eq!(
+                verification.verification_hash,
+                Felt::from_hex_unchecked(verification_hash)
+            );
+        }
+    }
+
+    #[test]
+    fn strict_rejects_other_bootloader() {
+        let mut public_input = fixtures::proof().public_input;
+        let entry = public_input
+            .main_page
+            .0
+            .iter_mut()
+            .find(|entry| entry.address == Felt::from(INITIAL_PC))
+            .unwrap();
+        entry.value += Felt::ONE;
+
+        assert!(matches!(
+            compute_fact(&public_input, MemoryVerification::Strict),
+            Err(FactError::MemoryMismatch { address, expected, actual })
+                if address == Felt::from(INITIAL_PC)
+                    && expected == INSTRUCTION_ADD_AP
+                    && actual == INSTRUCTION_ADD_AP + Felt::ONE
+        ));
+
+        // Relaxed mode accepts any program, which changes the program hash.
+        let fact = compute_fact(&public_input, MemoryVerification::Relaxed).unwrap();
+        assert_ne!(fact.program_hash, PROGRAM_HASH);
+        assert_eq!(fact.output_hash, OUTPUT_HASH);
+    }
+
+    #[test]
+    fn rejects_malformed_public_memory() {
+        let public_input = fixtures::proof().public_input;
+
+        let mut missing_output = public_input.clone();
+        missing_output.segments.truncate(OUTPUT_SEGMENT);
+        assert!(matches!(
+            compute_fact(&missing_output, MemoryVerification::Relaxed),
+            Err(FactError::SegmentMissing {
+                index: OUTPUT_SEGMENT
+            })
+        ));
+
+        let mut other_pc = public_input.clone();
+        other_pc.segments[PROGRAM_SEGMENT].begin_addr = Felt::TWO;
+        assert!(matches!(
+            compute_fact(&other_pc, MemoryVerification::Strict),
+            Err(FactError::InvalidInitialPc(pc)) if pc == Felt::TWO
+        ));
+
+        let mut missing_memory = public_input.clone();
+        missing_memory
+            .main_page
+            .0
+            .retain(|entry| entry.address != Felt::from(INITIAL_PC));
+        assert!(matches!(
+            compute_fact(&missing_memory, MemoryVerification::Relaxed),
+            Err(FactError::MemoryMissing(address)) if address == Felt::from(INITIAL_PC)
+        ));
+
+        let mut long_output = public_input;
+        long_output.segments[OUTPUT_SEGMENT].stop_ptr += Felt::from(long_output.main_page.len());
+        assert!(matches!(
+            compute_fact(&long_output, MemoryVerification::Cairo1),
+            Err(FactError::OutputTooLong { .. })
+        ));
+    }
+}
@@ -0,0 +1,43 @@
+use starknet_core::codec::Decode;
+use swiftness_stark::types::StarkProof;
+
+use crate::{
+    bindings::{StarkProofWithSerde, VerifierConfiguration},
+    config::{Hasher, Layout, MemoryVerification, StoneVersion},
+    split::split_proof_dyn,
+    Felt, IntegrityCalls,
+};
+
+/// The proof fixture shipped with `swiftness`, generated with the `recursive` layout and
+/// `keccak_160_lsb` hasher by Stone 5, encoded as calldata felts.
+const PROOF: &str = include_str!("../test-data/recursive_keccak_160_lsb_stone5_proof.txt");
+
+/// Decodes the [`PROOF`] fixture.
+pub(crate) fn proof() -> StarkProof {
+    let felts = PROOF
+        .split_whitespace()
+        .map(|token| token.parse().unwrap())
+        .collect::<Vec<Felt>>();
+    StarkProofWithSerde::decode(&felts)
+        .unwrap()
+        .try_into()
+        .unwrap()
+}
+
+/// The configuration [`proof`] verifies with.
+pub(crate) fn verifier_config() -> VerifierConfiguration {
+    VerifierConfiguration::new(
+        Layout::Recursive,
+        Hasher::Keccak160Lsb,
+        StoneVersion::Stone5,
+        MemoryVerification::Strict,
+    )
+}
+
+/// Splits [`proof`] into calls for the job `job_id`.
+pub(crate) fn calls(job_id: Felt) -> IntegrityCalls {
+    split_proof_dyn(proof(), verifier_config())
+        .unwrap()
+        .into_calls(job_id)
+        .unwrap()
+}
@@ -0,0 +1,263 @@
+use starknet_core::types::{Felt, NonZeroFelt};
+use swiftness_commitment::{
+    table::{
+        config::Config as SwiftnessTableCommitmentConfig,
+        types::{
+            Commitment as SwiftnessTableCommitment, Decommitment as SwiftnessTableDecommitment,
+        },
+    },
+    vector::{
+        config::Config as SwiftnessVectorCommitmentConfig,
+        types::Commitment as SwiftnessVectorCommitment,
+    },
+};
+use swiftness_fri::{
+    group::get_fri_group,
+    last_layer,
+    layer::{
+        compute_next_layer, FriError, FriLayerComputationParams,
+        FriLayerQuery as SwiftnessFriLayerQuery,
+    },
+};
+
+use crate::{
+    bindings::{
+        FriLayerQuery, FriLayerWitness, FriVerificationStateConstant, FriVerificationStateVariable,
+        TableCommitment,
+    },
+    config::{ConfigError, Hasher},
+    crypto::{table_decommit, DecommitError},
+};
+
+/// Smallest number of FRI layers accepted by the verifier, including the first and last layers.
+pub(crate) const MIN_FRI_LAYERS: u64 = 2;
+/// Largest number of FRI layers accepted by the verifier, including the first and last layers.
+pub(crate) const MAX_FRI_LAYERS: u64 = 15;
+/// Smallest FRI step size accepted by the verifier, except for the first layer.
+pub(crate) const MIN_FRI_STEP_SIZE: u64 = 1;
+/// Largest FRI step size accepted by the verifier.
+pub(crate) const MAX_FRI_STEP_SIZE: u64 = 4;
+/// Largest log2 of the last FRI layer's degree bound accepted by the verifier.
+pub(crate) const MAX_LAST_LAYER_LOG_DEGREE_BOUND: u64 = 15;
+
+/// Errors from verifying a single FRI layer.
+#[derive(Debug, thiserror::Error)]
+pub enum FriLayerError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("FRI layer {layer} is out of range for {n_layers} layers")]
+    LayerOutOfRange { layer: u32, n_layers: u32 },
+    #[error("FRI layer {layer} is missing from the state constant")]
+    LayerMissing { layer: u32 },
+    #[error("invalid FRI step size {step_size} for layer {layer}")]
+    InvalidStepSize { layer: u32, step_size: Felt },
+    #[error("FRI layer {layer} expects {expected} leaves, got {actual}")]
+    LeafCountMismatch {
+        layer: u32,
+        expected: usize,
+        actual: usize,
+    },
+    // `swiftness` errors are built without `std`, so they can't be chained as a `#[source]`.
+    #[error("FRI layer {layer} computation failed: {error}")]
+    LayerComputation { layer: u32, error: FriError },
+    #[error("FRI layer {layer} decommitment failed: {error}")]
+    Decommitment {
+        layer: u32,
+        #[source]
+        error: DecommitError,
+    },
+}
+
+/// Errors from verifying the last FRI layer against its coefficients.
+#[derive(Debug, thiserror::Error)]
+pub enum LastLayerError {
+    #[error("query {index:#x} has a zero x_inv_value")]
+    ZeroXInvValue { index: Felt },
+    #[error(
+        "last layer polynomial evaluates to {computed:#x} at query {index:#x} instead of \
+        {expected:#x}"
+    )]
+    QueryMismatch {
+        index: Felt,
+        expected: Felt,
+        computed: Felt,
+    },
+}
+
+/// Counts the coset elements not covered by any query, each of which must be supplied as a leaf.
+///
+/// This mirrors how `compute_next_layer` consumes queries and sibling leaves, which panics when
+/// leaves are missing.
+pub(crate) fn expected_sibling_count(
+    query_indices: impl IntoIterator<Item = Felt>,
+    coset_size: u64,
+) -> usize {
+    let coset_size_nonzero = NonZeroFelt::from_felt_unchecked(coset_size.into());
+
+    let mut count = 0;
+    let mut indices = query_indices.into_iter().peekable();
+    while let Some(index) = indices.peek() {
+        let coset_start = index.floor_div(&coset_size_nonzero) * Felt::from(coset_size);
+        for offset in 0..coset_size {
+            if indices
+                .peek()
+                .is_some_and(|index| *index == coset_start + Felt::from(offset))
+            {
+                indices.next();
+            } else {
+                count += 1;
+            }
+        }
+    }
+
+    count
+}
+
+/// Runs the checks of a single FRI layer, returning the state variable for the next layer.
+pub(crate) fn verify_layer(
+    hasher: Hasher,
+    state_constant: &FriVerificationStateConstant,
+    state_variable: &FriVerificationStateVariable,
+    witness: &FriLayerWitness,
+) -> Result<FriVerificationStateVariable, FriLayerError> {
+    let layer = state_variable.iter;
+    if layer >= state_constant.n_layers {
+        return Err(FriLayerError::LayerOutOfRange {
+            layer,
+            n_layers: state_constant.n_layers,
+        });
+    }
+
+    let index = layer as usize;
+    let (Some(commitment), Some(eval_point), Some(step_size)) = (
+        state_constant.commitment.get(index),
+        state_constant.eval_points.get(index),
+        state_constant.step_sizes.get(index),
+    ) else {
+        return Err(FriLayerError::LayerMissing { layer });
+    };
+
+    let coset_size = match u64::try_from(*step_size) {
+        Ok(step_size) if (MIN_FRI_STEP_SIZE..=MAX_FRI_STEP_SIZE).contains(&step_size) => {
+            1u64 << step_size
+        }
+        _ => {
+            return Err(FriLayerError::InvalidStepSize {
+                layer,
+                step_size: *step_size,
+            })
+        }
+    };
+
+    // `compute_next_layer` panics on missing siblings, so the leaf count is checked upfront.
+    let expected_leaves = expected_sibling_count(
+        state_variable.queries.iter().map(|query| query.index),
+        coset_size,
+    );
+    if expected_leaves != witness.leaves.len() {
+        return Err(FriLayerError::LeafCountMismatch {
+            layer,
+            expected: expected_leaves,
+            actual: witness.leaves.len(),
+        });
+    }
+
+    let (next_queries, verify_indices, verify_y_values) = compute_next_layer(
+        &mut state_variable
+            .queries
+            .iter()
+            .map(to_swiftness_query)
+            .collect(),
+        &mut witness.leaves.clone(),
+        FriLayerComputationParams {
+            coset_size: coset_size.into(),
+            fri_group: get_fri_group(),
+            eval_point: *eval_point,
+        },
+    )
+    .map_err(|error| FriLayerError::LayerComputation { layer, error })?;
+
+    table_decommit(
+        hasher,
+        &to_swiftness_table_commitment(commitment),
+        &verify_indices,
+        &SwiftnessTableDecommitment {
+            values: verify_y_values,
+        },
+        &witness.table_witness.vector.authentications,
+    )
+    .map_err(|error| FriLayerError::Decommitment { layer, error })?;
+
+    Ok(FriVerificationStateVariable {
+        iter: layer + 1,
+        queries: next_queries.into_iter().map(to_binding_query).collect(),
+    })
+}
+
+/// Checks that the last layer polynomial evaluates to the query values.
+pub(crate) fn verify_last_layer(
+    queries: &[FriLayerQuery],
+    coefficients: &[Felt],
+) -> Result<(), LastLayerError> {
+    for query in queries {
+        // `verify_last_layer` panics on zero `x_inv_value`.
+        if query.x_inv_value == Felt::ZERO {
+            return Err(LastLayerError::ZeroXInvValue { index: query.index });
+        }
+
+        // Queries are checked one by one so that the failing one can be reported.
+        last_layer::verify_last_layer(vec![to_swiftness_query(query)], coefficients.to_vec())
+            .map_err(|last_layer::Error::QueryMismatch { expected, got }| {
+                LastLayerError::QueryMismatch {
+                    index: query.index,
+                    expected,
+                    computed: got,
+                }
+            })?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn to_swiftness_query(query: &FriLayerQuery) -> SwiftnessFriLayerQuery {
+    SwiftnessFriLayerQuery {
+        index: query.index,
+        y_value: query.y_value,
+        x_inv_value: query.x_inv_value,
+    }
+}
+
+pub(crate) fn to_binding_query(query: SwiftnessFriLayerQuery) -> FriLayerQuery {
+    FriLayerQuery {
+        index: query.index,
+        y_value: query.y_value,
+        x_inv_value: query.x_inv_value,
+    }
+}
+
+pub(crate) fn to_swiftness_table_commitment(
+    commitment: &TableCommitment,
+) -> SwiftnessTableCommitment {
+    SwiftnessTableCommitment {
+        config: SwiftnessTableCommitmentConfig {
+            n_columns: commitment.config.n_columns,
+            vector: SwiftnessVectorCommitmentConfig {
+                height: commitment.config.vector.height,
+                n_verifier_friendly_commitment_layers: commitment
+                    .config
+                    .vector
+                    .n_verifier_friendly_commitment_layers,
+            },
+        },
+        vector_commitment: SwiftnessVectorCommitment {
+            config: SwiftnessVectorCommitmentConfig {
+                height: commitment.vector_commitment.config.height,
+                n_verifier_friendly_commitment_layers: commitment
+                    .vector_commitment
+                    .config
+                    .n_verifier_friendly_commitment_layers,
+            },
+            commitment_hash: commitment.vector_commitment.commitment_hash,
+        },
+    }
+}
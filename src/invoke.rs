@@ -0,0 +1,388 @@
+use starknet_core::{
+    crypto::ecdsa_sign,
+    types::{
+        requests::AddInvokeTransactionRequest, BroadcastedInvokeTransaction,
+        BroadcastedInvokeTransactionV3, Call, DataAvailabilityMode, Felt, ResourceBounds,
+        ResourceBoundsMapping,
+    },
+};
+use starknet_crypto::poseidon_hash_many;
+
+use crate::{
+    pack::{execute_calldata, PackError, PackedTransaction, StepEstimator, TransactionPacker},
+    IntegrityCalls,
+};
+
+/// Cairo short string `invoke`, the prefix of `INVOKE` transaction hashes.
+const PREFIX_INVOKE: Felt = Felt::from_raw([
+    513398556346534256,
+    18446744073709551615,
+    18446744073709551615,
+    18443034532770911073,
+]);
+
+/// Cairo short string `L1_GAS`, the resource name used in transaction hashes.
+const RESOURCE_L1_GAS: [u8; 6] = *b"L1_GAS";
+
+/// Cairo short string `L2_GAS`, the resource name used in transaction hashes.
+const RESOURCE_L2_GAS: [u8; 6] = *b"L2_GAS";
+
+/// Builds `INVOKE` v3 transactions sent from a single account.
+#[derive(Debug, Clone)]
+pub struct InvokeTransactionBuilder {
+    sender_address: Felt,
+    chain_id: Felt,
+    resource_bounds: ResourceBoundsMapping,
+    tip: u64,
+}
+
+/// A private key signing transactions locally, without any network access.
+#[derive(Clone)]
+pub struct LocalSigner {
+    private_key: Felt,
+}
+
+/// A signed `INVOKE` v3 transaction ready to be broadcast.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignedInvokeTransaction {
+    /// Hash of the transaction.
+    pub transaction_hash: Felt,
+    /// The transaction, including its signature.
+    pub transaction: BroadcastedInvokeTransactionV3,
+}
+
+/// A JSON-RPC request for broadcasting a transaction with `starknet_addInvokeTransaction`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AddInvokeTransactionPayload {
+    /// JSON-RPC version, always `2.0`.
+    pub jsonrpc: String,
+    /// ID of the request.
+    pub id: u64,
+    /// Method name, always `starknet_addInvokeTransaction`.
+    pub method: String,
+    /// Parameters of the method.
+    pub params: AddInvokeTransactionRequest,
+}
+
+/// Errors from building and signing `INVOKE` transactions.
+#[derive(Debug, thiserror::Error)]
+pub enum InvokeError {
+    #[error(transparent)]
+    Pack(#[from] PackError),
+    #[error("transaction hash {0:#066x} is out of range for signing")]
+    HashOutOfRange(Felt),
+}
+
+impl InvokeTransactionBuilder {
+    /// Creates a new [`InvokeTransactionBuilder`] for transactions sent by `sender_address` on the
+    /// network identified by `chain_id`, without any tip.
+    pub fn new(
+        sender_address: Felt,
+        chain_id: Felt,
+        resource_bounds: ResourceBoundsMapping,
+    ) -> Self {
+        Self {
+            sender_address,
+            chain_id,
+            resource_bounds,
+            tip: 0,
+        }
+    }
+
+    /// Sets the tip paid by each transaction.
+    pub fn tip(mut self, tip: u64) -> Self {
+        self.tip = tip;
+        self
+    }
+
+    /// Gets the address of the account sending the transactions.
+    pub fn sender_address(&self) -> Felt {
+        self.sender_address
+    }
+
+    /// Raises the max price per unit of all resources by `percent`.
+    pub(crate) fn bump_prices(&mut self, percent: u64) {
+        for bounds in [
+            &mut self.resource_bounds.l1_gas,
+            &mut self.resource_bounds.l2_gas,
+        ] {
+            bounds.max_price_per_unit = bounds
+                .max_price_per_unit
+                .checked_mul(100 + u128::from(percent))
+                .map_or(u128::MAX, |price| price / 100);
+        }
+    }
+
+    /// Computes the hash of the transaction executing `calls` with `nonce`.
+    pub fn transaction_hash(&self, calls: &[Call], nonce: Felt) -> Felt {
+        self.hash(&execute_calldata(calls), nonce)
+    }
+
+    /// Builds and signs the transaction executing `calls` with `nonce`.
+    pub fn sign(
+        &self,
+        calls: &[Call],
+        nonce: Felt,
+        signer: &LocalSigner,
+    ) -> Result<SignedInvokeTransaction, InvokeError> {
+        let calldata = execute_calldata(calls);
+        let transaction_hash = self.hash(&calldata, nonce);
+
+        Ok(SignedInvokeTransaction {
+            transaction_hash,
+            transaction: BroadcastedInvokeTransactionV3 {
+                sender_address: self.sender_address,
+                calldata,
+                signature: signer.sign(transaction_hash)?,
+                nonce,
+                resource_bounds: self.resource_bounds.clone(),
+                tip: self.tip,
+                paymaster_data: vec![],
+                account_deployment_data: vec![],
+                nonce_data_availability_mode: DataAvailabilityMode::L1,
+                fee_data_availability_mode: DataAvailabilityMode::L1,
+                is_query: false,
+            },
+        })
+    }
+
+    /// Signs packed transactions in order, assigning increasing nonces from `first_nonce`.
+    pub fn sign_transactions(
+        &self,
+        transactions: &[PackedTransaction],
+        first_nonce: Felt,
+        signer: &LocalSigner,
+    ) -> Result<Vec<SignedInvokeTransaction>, InvokeError> {
+        let mut nonce = first_nonce;
+        transactions
+            .iter()
+            .map(|transaction| {
+                let signed = self.sign(&transaction.calls, nonce, signer)?;
+                nonce += Felt::ONE;
+                Ok(signed)
+            })
+            .collect()
+    }
+
+    /// Packs all calls of a verification job against the `integrity` contract at
+    /// `contract_address` and signs the resulting transactions, assigning increasing nonces from
+    /// `first_nonce`.
+    pub fn sign_job<E>(
+        &self,
+        calls: IntegrityCalls,
+        contract_address: Felt,
+        packer: &TransactionPacker<E>,
+        first_nonce: Felt,
+        signer: &LocalSigner,
+    ) -> Result<Vec<SignedInvokeTransaction>, InvokeError>
+    where
+        E: StepEstimator,
+    {
+        let transactions = packer.pack(calls, contract_address)?;
+        self.sign_transactions(&transactions, first_nonce, signer)
+    }
+
+    fn hash(&self, calldata: &[Felt], nonce: Felt) -> Felt {
+        let fee_fields_hash = poseidon_hash_many(&[
+            self.tip.into(),
+            encode_resource_bounds(RESOURCE_L1_GAS, &self.resource_bounds.l1_gas),
+            encode_resource_bounds(RESOURCE_L2_GAS, &self.resource_bounds.l2_gas),
+        ]);
+
+        poseidon_hash_many(&[
+            PREFIX_INVOKE,
+            Felt::THREE,
+            self.sender_address,
+            fee_fields_hash,
+            // `paymaster_data`
+            poseidon_hash_many(&[]),
+            self.chain_id,
+            nonce,
+            // Nonce and fee data availability modes, both `L1`
+            Felt::ZERO,
+            // `account_deployment_data`
+            poseidon_hash_many(&[]),
+            poseidon_hash_many(calldata),
+        ])
+    }
+}
+
+impl LocalSigner {
+    /// Creates a new [`LocalSigner`] from a private key.
+    pub fn new(private_key: Felt) -> Self {
+        Self { private_key }
+    }
+
+    /// Gets the public key of the signer.
+    pub fn public_key(&self) -> Felt {
+        starknet_crypto::get_public_key(&self.private_key)
+    }
+
+    /// Signs `hash`, returning the `r` and `s` values of the signature.
+    pub fn sign(&self, hash: Felt) -> Result<Vec<Felt>, InvokeError> {
+        let signature =
+            ecdsa_sign(&self.private_key, &hash).map_err(|_| InvokeError::HashOutOfRange(hash))?;
+        Ok(vec![signature.r, signature.s])
+    }
+}
+
+// Manually implemented to keep the private key out of logs.
+impl std::fmt::Debug for LocalSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalSigner")
+            .field("public_key", &self.public_key())
+            .finish_non_exhaustive()
+    }
+}
+
+impl SignedInvokeTransaction {
+    /// Wraps the transaction into a `starknet_addInvokeTransaction` JSON-RPC request with `id`.
+    pub fn add_invoke_transaction_payload(&self, id: u64) -> AddInvokeTransactionPayload {
+        AddInvokeTransactionPayload {
+            jsonrpc: "2.0".into(),
+            id,
+            method: "starknet_addInvokeTransaction".into(),
+            params: AddInvokeTransactionRequest {
+                invoke_transaction: BroadcastedInvokeTransaction::V3(self.transaction.clone()),
+            },
+        }
+    }
+}
+
+/// Packs a resource name, max amount and max price per unit into a single felt, as hashed by
+/// `INVOKE` v3 transactions.
+fn encode_resource_bounds(name: [u8; 6], bounds: &ResourceBounds) -> Felt {
+    let mut buffer = [0u8; 32];
+    buffer[2..8].copy_from_slice(&name);
+    buffer[8..16].copy_from_slice(&bounds.max_amount.to_be_bytes());
+    buffer[16..].copy_from_slice(&bounds.max_price_per_unit.to_be_bytes());
+    Felt::from_bytes_be(&buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet_core::{chain_id, utils::get_selector_from_name};
+
+    use super::*;
+
+    const SENDER_ADDRESS: Felt = Felt::from_hex_unchecked(
+        "0x3f6f3bc663aedc5285d6013cc3ffcbc4341d86ab488b8b68d297f8258793c41",
+    );
+
+    fn builder() -> InvokeTransactionBuilder {
+        InvokeTransactionBuilder::new(
+            SENDER_ADDRESS,
+            chain_id::SEPOLIA,
+            ResourceBoundsMapping {
+                l1_gas: ResourceBounds {
+                    max_amount: 0x186a0,
+                    max_price_per_unit: 0x5af3107a4000,
+                },
+                l2_gas: ResourceBounds {
+                    max_amount: 0,
+                    max_price_per_unit: 0,
+                },
+            },
+        )
+    }
+
+    fn calls() -> Vec<Call> {
+        vec![Call {
+            to: Felt::from_hex_unchecked(
+                "0x4c1337d55351eac9a0b74f3b8f0d3928e2bb781e5084686a892e66d49d510d",
+            ),
+            selector: get_selector_from_name("transfer").unwrap(),
+            calldata: vec![Felt::from(0x1234u32), Felt::from(100u32), Felt::ZERO],
+        }]
+    }
+
+    fn signer() -> LocalSigner {
+        LocalSigner::new(Felt::from_hex_unchecked(
+            "0x4a1e5b5c6b1a0b1e7d6b8e4f4b2c0a3c9d1e2f3a4b5c6d7e8f90a1b2c3d4e5f",
+        ))
+    }
+
+    #[test]
+    fn transaction_hash_known_answer() {
+        let nonce = Felt::from(0x8a9u32);
+
+        // Fields of the `INVOKE` v3 hash as listed by SNIP-8, with resource bounds packed as
+        // `name << 192 | max_amount << 128 | max_price_per_unit`.
+        let l1_gas = Felt::from_bytes_be_slice(b"L1_GAS") * Felt::TWO.pow(192u32)
+            + Felt::from(0x186a0u32) * Felt::TWO.pow(128u32)
+            + Felt::from(0x5af3107a4000u64);
+        let l2_gas = Felt::from_bytes_be_slice(b"L2_GAS") * Felt::TWO.pow(192u32);
+        let expected = poseidon_hash_many(&[
+            Felt::from_bytes_be_slice(b"invoke"),
+            Felt::THREE,
+            SENDER_ADDRESS,
+            poseidon_hash_many(&[Felt::ZERO, l1_gas, l2_gas]),
+            poseidon_hash_many(&[]),
+            chain_id::SEPOLIA,
+            nonce,
+            Felt::ZERO,
+            poseidon_hash_many(&[]),
+            poseidon_hash_many(&execute_calldata(&calls())),
+        ]);
+
+        let hash = builder().transaction_hash(&calls(), nonce);
+        assert_eq!(hash, expected);
+
+        // Pinned so that changes to the field list above are caught too.
+        assert_eq!(
+            hash,
+            Felt::from_hex_unchecked(
+                "0x07ace71d4b82e7378a1fc2f562872cd5df7f3a2c04293a5dc8337ee43870a56b"
+            )
+        );
+    }
+
+    #[test]
+    fn signature_verifies_against_public_key() {
+        let signer = signer();
+        let signed = builder().sign(&calls(), Felt::ONE, &signer).unwrap();
+
+        let [r, s] = signed.transaction.signature[..] else {
+            panic!("expected an `r` and `s` signature");
+        };
+        assert!(
+            starknet_crypto::verify(&signer.public_key(), &signed.transaction_hash, &r, &s)
+                .unwrap()
+        );
+        assert!(!starknet_crypto::verify(
+            &signer.public_key(),
+            &(signed.transaction_hash + Felt::ONE),
+            &r,
+            &s
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn signs_transactions_with_consecutive_nonces() {
+        let transactions = (0..3)
+            .map(|_| PackedTransaction {
+                calls: calls(),
+                calldata_len: execute_calldata(&calls()).len(),
+                estimated_steps: 0,
+            })
+            .collect::<Vec<_>>();
+        let first_nonce = Felt::from(7u32);
+
+        let signed = builder()
+            .sign_transactions(&transactions, first_nonce, &signer())
+            .unwrap();
+
+        assert_eq!(signed.len(), 3);
+        for (offset, transaction) in signed.iter().enumerate() {
+            let nonce = first_nonce + Felt::from(offset);
+            assert_eq!(transaction.transaction.nonce, nonce);
+            assert_eq!(
+                transaction.transaction_hash,
+                builder().transaction_hash(&calls(), nonce)
+            );
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use starknet_core::{codec::Encode, types::Felt};
+use starknet_crypto::poseidon_hash_many;
+
+use crate::{
+    bindings::{StarkProofWithSerde, VerifierConfiguration},
+    split::{MaterializedSplitProof, SplitProof},
+};
+
+/// A job ID shared by more than one job.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobIdCollision {
+    /// The colliding job ID.
+    pub job_id: Felt,
+    /// Positions of the jobs using the ID, in ascending order.
+    pub indices: Vec<usize>,
+}
+
+/// Derives a job ID from the content of a proof, so that the same proof, verifier configuration
+/// and `namespace` always map to the same ID.
+///
+/// The ID is the Poseidon hash of `namespace`, the verifier configuration hash, and the Poseidon
+/// hashes of the encoded public input and unsent commitment. Using a distinct `namespace` per
+/// tenant keeps their IDs apart even when they verify the same proof, while a nonce can be mixed
+/// into `namespace` to verify a proof more than once.
+pub fn derive_job_id(
+    proof: &StarkProofWithSerde,
+    verifier_config: &VerifierConfiguration,
+    namespace: Felt,
+) -> Felt {
+    poseidon_hash_many(&[
+        namespace,
+        verifier_config.hash(),
+        encoded_hash(&proof.public_input),
+        encoded_hash(&proof.unsent_commitment),
+    ])
+}
+
+/// Finds job IDs that appear more than once, in the order they first appear.
+pub fn find_job_id_collisions(job_ids: impl IntoIterator<Item = Felt>) -> Vec<JobIdCollision> {
+    let mut order = vec![];
+    let mut indices_by_id = HashMap::<Felt, Vec<usize>>::new();
+    for (index, job_id) in job_ids.into_iter().enumerate() {
+        let indices = indices_by_id.entry(job_id).or_insert_with(|| {
+            order.push(job_id);
+            vec![]
+        });
+        indices.push(index);
+    }
+
+    order
+        .into_iter()
+        .filter_map(|job_id| {
+            let indices = indices_by_id.remove(&job_id)?;
+            (indices.len() > 1).then_some(JobIdCollision { job_id, indices })
+        })
+        .collect()
+}
+
+impl SplitProof {
+    /// Derives the job ID for this proof with [`derive_job_id`].
+    pub fn derive_job_id(&self, namespace: Felt) -> Felt {
+        derive_job_id(&self.proof, &self.verifier_config, namespace)
+    }
+}
+
+impl MaterializedSplitProof {
+    /// Derives the job ID for this proof with [`derive_job_id`].
+    pub fn derive_job_id(&self, namespace: Felt) -> Felt {
+        derive_job_id(&self.proof, &self.verifier_config, namespace)
+    }
+}
+
+fn encoded_hash<T: Encode>(value: &T) -> Felt {
+    let mut encoded = vec![];
+
+    // This type never fails to serialize
+    value.encode(&mut encoded).unwrap();
+
+    poseidon_hash_many(&encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(all(feature = "recursive", feature = "keccak_160_lsb", feature = "stone5"))]
+    #[test]
+    fn derives_job_id_from_content() {
+        use crate::fixtures;
+
+        let proof = StarkProofWithSerde::from(fixtures::proof());
+        let verifier_config = fixtures::verifier_config();
+        let job_id = derive_job_id(&proof, &verifier_config, Felt::ONE);
+
+        assert_eq!(
+            derive_job_id(&proof.clone(), &verifier_config, Felt::ONE),
+            job_id
+        );
+        assert_ne!(derive_job_id(&proof, &verifier_config, Felt::TWO), job_id);
+
+        let mut changed = proof.clone();
+        changed.public_input.main_page[0].value += Felt::ONE;
+        assert_ne!(derive_job_id(&changed, &verifier_config, Felt::ONE), job_id);
+
+        let mut changed = proof.clone();
+        changed.unsent_commitment.composition += Felt::ONE;
+        assert_ne!(derive_job_id(&changed, &verifier_config, Felt::ONE), job_id);
+    }
+
+    #[test]
+    fn reports_duplicated_job_ids() {
+        let job_ids = [1u64, 2, 3, 2, 4, 1, 2].map(Felt::from);
+
+        assert_eq!(
+            find_job_id_collisions(job_ids),
+            [
+                JobIdCollision {
+                    job_id: Felt::ONE,
+                    indices: vec![0, 5],
+                },
+                JobIdCollision {
+                    job_id: Felt::TWO,
+                    indices: vec![1, 3, 6],
+                },
+            ]
+        );
+        assert!(find_job_id_collisions([1u64, 2, 3].map(Felt::from)).is_empty());
+    }
+}
@@ -16,15 +16,111 @@ use starknet_core::types::Call;
 /// Bindings for the `integrity` contract.
 pub mod bindings;
 pub use bindings::{
-    StarkProofWithSerde, VerifierConfiguration, VerifyProofFinalAndRegisterFactCall,
-    VerifyProofInitialCall, VerifyProofStepCall,
+    GetAllVerificationsForFactHashCall, GetVerificationCall, StarkProofWithSerde, Verification,
+    VerificationListElement, VerifierConfiguration, VerifyProofFinalAndRegisterFactCall,
+    VerifyProofFullAndRegisterFactCall, VerifyProofInitialCall, VerifyProofStepCall,
 };
 
+mod commit;
+
+mod error;
+pub use error::Error;
+
+// The proof fixture needs the layout, hasher and Stone version it was generated with.
+#[cfg(all(
+    test,
+    feature = "recursive",
+    feature = "keccak_160_lsb",
+    feature = "stone5"
+))]
+mod fixtures;
+
+/// Verifier configuration parsing.
+pub mod config;
+pub use config::{ConfigError, Hasher, Layout, MemoryVerification, StoneVersion};
+
+/// Known deployments of the `integrity` contracts.
+pub mod deployment;
+pub use deployment::{Deployment, DeploymentRegistry, Network, RegistryError, VerifierDeployment};
+
+/// Decoding of `integrity` contract events.
+pub mod event;
+pub use event::{find_fact_registered, EventError, FactRegistered};
+
+/// Fact and verification hash computation.
+pub mod fact;
+pub use fact::{
+    compute_fact, compute_verification, security_bits, verification_hash, FactError, ProofFact,
+    ProofVerification,
+};
+
+mod fri;
+pub use fri::{FriLayerError, LastLayerError};
+
+/// Deterministic job ID derivation.
+pub mod job;
+pub use job::{derive_job_id, find_job_id_collisions, JobIdCollision};
+
+/// Building and signing of `INVOKE` v3 transactions.
+pub mod invoke;
+pub use invoke::{
+    AddInvokeTransactionPayload, InvokeError, InvokeTransactionBuilder, LocalSigner,
+    SignedInvokeTransaction,
+};
+
+// Hasher-dependent primitives are reimplemented here as `swiftness` can only be compiled with a
+// single hasher.
+mod crypto;
+pub use crypto::DecommitError;
+
+/// Off-chain replica of the `integrity` verifier contract for dry runs.
+pub mod mock;
+pub use mock::{MockIntegrity, MockIntegrityError, MockProvider, RegisteredFact};
+
+/// Packing of contract calls into transactions under size limits.
+pub mod pack;
+pub use pack::{
+    LinearStepEstimator, PackError, PackedTransaction, StepEstimator, TransactionLimits,
+    TransactionPacker,
+};
+
+/// Off-chain checks of verification steps before submission.
+pub mod preflight;
+pub use preflight::{preflight, PreflightError};
+
+/// Interop with the calldata files of the `integrity` reference proof serializer.
+pub mod reference;
+pub use reference::{CalldataDiff, ReferenceCalldata, ReferenceError};
+
+/// Orchestration of verification job submission.
+pub mod submit;
+pub use submit::{
+    JobProgress, Orchestrator, ProviderError, SubmissionConfig, SubmissionError,
+    SubmissionProvider, SubmissionReport, TransactionStatus,
+};
+
+/// Calldata encoding annotated with the source field of each felt.
+pub mod source_map;
+pub use source_map::{AnnotatedFelt, CalldataSourceMap};
+
+/// Persistent storage of split jobs and their submission progress.
+#[cfg(feature = "store")]
+pub mod store;
+#[cfg(feature = "sqlite")]
+pub use store::SqliteJobStore;
+#[cfg(feature = "store")]
+pub use store::{CallRecord, CallStatus, FileJobStore, JobRecord, JobStore, StoreError};
+
 mod split;
-pub use split::{split_proof, SplitProof, VerifyProofStepParamIter};
+pub use split::{
+    split_and_verify, split_and_verify_dyn, split_proof, split_proof_dyn, verification_calls,
+    MaterializedSplitProof, SplitProof, SplitProofFinalStep, SplitProofStep, VerifyProofStepParam,
+    VerifyProofStepParamIter,
+};
 
 /// Contract bindings for all contract calls needed to verify a STARK proof on-chain.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IntegrityCalls {
     /// The initial verification call.
     pub initial: VerifyProofInitialCall,
@@ -34,6 +130,26 @@ pub struct IntegrityCalls {
     pub final_step: VerifyProofFinalAndRegisterFactCall,
 }
 
+/// Contract calls for verifying a STARK proof, either in a single call or split into steps.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VerificationCalls {
+    /// A single call verifying the whole proof.
+    Full(Box<VerifyProofFullAndRegisterFactCall>),
+    /// Calls verifying the proof over multiple steps.
+    Split(Box<IntegrityCalls>),
+}
+
+/// Contract bindings for the calls remaining after resuming a partially submitted verification job.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RemainingCalls {
+    /// The intermediate verification step calls not submitted yet.
+    pub intermediate_steps: Vec<VerifyProofStepCall>,
+    /// The final verification call.
+    pub final_step: VerifyProofFinalAndRegisterFactCall,
+}
+
 impl IntegrityCalls {
     /// Flattens the calls into a list of [`Call`] ready for use with `starknet-rs`.
     pub fn collect_calls(self, contract_address: Felt) -> Vec<Call> {
@@ -47,3 +163,26 @@ impl IntegrityCalls {
         calls
     }
 }
+
+impl VerificationCalls {
+    /// Flattens the calls into a list of [`Call`] ready for use with `starknet-rs`.
+    pub fn collect_calls(self, contract_address: Felt) -> Vec<Call> {
+        match self {
+            Self::Full(call) => vec![call.call(contract_address)],
+            Self::Split(calls) => calls.collect_calls(contract_address),
+        }
+    }
+}
+
+impl RemainingCalls {
+    /// Flattens the calls into a list of [`Call`] ready for use with `starknet-rs`.
+    pub fn collect_calls(self, contract_address: Felt) -> Vec<Call> {
+        let mut calls = self
+            .intermediate_steps
+            .into_iter()
+            .map(|step| step.call(contract_address))
+            .collect::<Vec<_>>();
+        calls.push(self.final_step.call(contract_address));
+        calls
+    }
+}
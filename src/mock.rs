@@ -0,0 +1,498 @@
+use std::collections::HashMap;
+
+use crate::{
+    bindings::{
+        FriVerificationStateConstant, FriVerificationStateVariable, VerifierConfiguration,
+        VerifyProofFinalAndRegisterFactCall, VerifyProofInitialCall, VerifyProofStepCall,
+        SELECTOR_VERIFY_PROOF_FINAL_AND_REGISTER_FACT_CALL, SELECTOR_VERIFY_PROOF_INITIAL_CALL,
+        SELECTOR_VERIFY_PROOF_STEP_CALL,
+    },
+    config::{ConfigError, Hasher},
+    error::Error,
+    fact::{compute_fact, security_bits, verification_hash, FactError, ProofFact},
+    fri::{verify_last_layer, verify_layer, FriLayerError, LastLayerError},
+    invoke::SignedInvokeTransaction,
+    split::initial_fri_state,
+    submit::{JobProgress, ProviderError, SubmissionProvider, TransactionStatus},
+    IntegrityCalls,
+};
+use starknet_core::{
+    codec::{Decode, Error as CodecError},
+    types::{Call, Felt},
+};
+use starknet_crypto::poseidon_hash_many;
+use swiftness_stark::types::StarkProof as SwiftnessStarkProof;
+
+/// An off-chain replica of the `integrity` verifier contract's multi-step verification state
+/// machine, useful for dry-running [`IntegrityCalls`] before submitting them.
+///
+/// Only the split verification flow is replicated: the initial FRI state is derived from the proof
+/// and the FRI layer steps and the final step are fully checked, but the other checks performed by
+/// `verify_proof_initial` on the STARK proof itself are not.
+#[derive(Debug, Default, Clone)]
+pub struct MockIntegrity {
+    jobs: HashMap<Felt, JobState>,
+    facts: Vec<RegisteredFact>,
+}
+
+/// A [`SubmissionProvider`] executing transactions against a [`MockIntegrity`] instance as soon as
+/// they're broadcast.
+///
+/// Account nonces are tracked but signatures are not checked. Transactions are applied atomically:
+/// a reverted transaction leaves the contract state untouched.
+#[derive(Debug, Clone)]
+pub struct MockProvider {
+    contract_address: Felt,
+    integrity: MockIntegrity,
+    nonces: HashMap<Felt, Felt>,
+    transactions: HashMap<Felt, TransactionStatus>,
+}
+
+/// A fact registered by [`MockIntegrity`] upon successful completion of a verification job.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredFact {
+    /// ID of the job that registered the fact.
+    pub job_id: Felt,
+    /// The verifier configuration used for the job.
+    pub verifier_config: VerifierConfiguration,
+    /// The security bits of the verified proof.
+    pub security_bits: Felt,
+    /// The fact attested by the proof.
+    pub fact: ProofFact,
+    /// Hash identifying the verification in the fact registry.
+    pub verification_hash: Felt,
+}
+
+/// Errors from executing calls against [`MockIntegrity`].
+#[derive(Debug, thiserror::Error)]
+pub enum MockIntegrityError {
+    #[error("job {0:#066x} already exists")]
+    JobAlreadyExists(Felt),
+    #[error("job {0:#066x} does not exist")]
+    JobNotFound(Felt),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Fact(#[from] FactError),
+    #[error(transparent)]
+    FriLayer(#[from] FriLayerError),
+    #[error("failed to derive the initial FRI state: {0}")]
+    InitialState(#[source] Error),
+    #[error("FRI verification state constant differs from previous steps")]
+    StateConstantMismatch,
+    #[error("unexpected FRI layer iteration: expected {expected}, got {actual}")]
+    IterMismatch { expected: u32, actual: u32 },
+    #[error("FRI layer queries differ from the ones produced by the previous step")]
+    QueriesMismatch,
+    #[error("last layer coefficients do not match the committed hash")]
+    LastLayerCoefficientsHashMismatch,
+    #[error("last layer verification failed: {0}")]
+    LastLayer(#[source] LastLayerError),
+    #[error("unknown entrypoint selector {0:#066x}")]
+    UnknownSelector(Felt),
+    #[error("failed to decode calldata: {0}")]
+    Decode(CodecError),
+    #[error("no contract deployed at {address:#066x}, expected {expected:#066x}")]
+    ContractNotDeployed { address: Felt, expected: Felt },
+    #[error("step {index} failed: {error}")]
+    StepFailed {
+        index: usize,
+        error: Box<MockIntegrityError>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct JobState {
+    verifier_config: VerifierConfiguration,
+    hasher: Hasher,
+    security_bits: Felt,
+    /// Computed by the initial call, as the later calls don't carry the public input.
+    fact: ProofFact,
+    /// Derived from the proof by the initial call, then advanced by each step.
+    fri_state: (FriVerificationStateConstant, FriVerificationStateVariable),
+}
+
+impl MockIntegrity {
+    /// Creates a new [`MockIntegrity`] instance without any jobs or facts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Executes all calls of a verification job in order, returning the registered fact.
+    pub fn execute(
+        &mut self,
+        calls: &IntegrityCalls,
+    ) -> Result<RegisteredFact, MockIntegrityError> {
+        self.verify_proof_initial(&calls.initial)?;
+
+        for (index, step) in calls.intermediate_steps.iter().enumerate() {
+            self.verify_proof_step(step)
+                .map_err(|error| MockIntegrityError::StepFailed {
+                    index,
+                    error: Box::new(error),
+                })?;
+        }
+
+        self.verify_proof_final_and_register_fact(&calls.final_step)
+    }
+
+    /// Replicates the `verify_proof_initial` contract entrypoint.
+    pub fn verify_proof_initial(
+        &mut self,
+        call: &VerifyProofInitialCall,
+    ) -> Result<(), MockIntegrityError> {
+        // Like the contract, completed jobs keep their ID reserved.
+        if self.jobs.contains_key(&call.job_id)
+            || self.facts.iter().any(|fact| fact.job_id == call.job_id)
+        {
+            return Err(MockIntegrityError::JobAlreadyExists(call.job_id));
+        }
+
+        let hasher = call.verifier_config.hasher()?;
+        let proof = SwiftnessStarkProof::try_from(call.stark_proof.clone())
+            .map_err(MockIntegrityError::Decode)?;
+        let security_bits = security_bits(&proof.config);
+        let fact = compute_fact(
+            &proof.public_input,
+            call.verifier_config.memory_verification()?,
+        )?;
+        let fri_state = initial_fri_state(proof, call.verifier_config.clone())
+            .map_err(MockIntegrityError::InitialState)?;
+
+        self.jobs.insert(
+            call.job_id,
+            JobState {
+                verifier_config: call.verifier_config.clone(),
+                hasher,
+                security_bits,
+                fact,
+                fri_state,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Replicates the `verify_proof_step` contract entrypoint.
+    pub fn verify_proof_step(
+        &mut self,
+        call: &VerifyProofStepCall,
+    ) -> Result<(), MockIntegrityError> {
+        let job = self
+            .jobs
+            .get_mut(&call.job_id)
+            .ok_or(MockIntegrityError::JobNotFound(call.job_id))?;
+        job.check_fri_state(&call.state_constant, &call.state_variable)?;
+
+        let next_state_variable = verify_layer(
+            job.hasher,
+            &call.state_constant,
+            &call.state_variable,
+            &call.witness,
+        )?;
+        job.fri_state = (call.state_constant.clone(), next_state_variable);
+
+        Ok(())
+    }
+
+    /// Replicates the `verify_proof_final_and_register_fact` contract entrypoint.
+    pub fn verify_proof_final_and_register_fact(
+        &mut self,
+        call: &VerifyProofFinalAndRegisterFactCall,
+    ) -> Result<RegisteredFact, MockIntegrityError> {
+        let job = self
+            .jobs
+            .get(&call.job_id)
+            .ok_or(MockIntegrityError::JobNotFound(call.job_id))?;
+        job.check_fri_state(&call.state_constant, &call.state_variable)?;
+
+        if call.state_variable.iter != call.state_constant.n_layers {
+            return Err(MockIntegrityError::IterMismatch {
+                expected: call.state_constant.n_layers,
+                actual: call.state_variable.iter,
+            });
+        }
+
+        if poseidon_hash_many(&call.last_layer_coefficients)
+            != call.state_constant.last_layer_coefficients_hash
+        {
+            return Err(MockIntegrityError::LastLayerCoefficientsHashMismatch);
+        }
+
+        verify_last_layer(&call.state_variable.queries, &call.last_layer_coefficients)
+            .map_err(MockIntegrityError::LastLayer)?;
+
+        let job = self
+            .jobs
+            .remove(&call.job_id)
+            .ok_or(MockIntegrityError::JobNotFound(call.job_id))?;
+        let fact = RegisteredFact {
+            job_id: call.job_id,
+            verification_hash: verification_hash(
+                job.fact.fact_hash,
+                &job.verifier_config,
+                job.security_bits,
+            ),
+            verifier_config: job.verifier_config,
+            security_bits: job.security_bits,
+            fact: job.fact,
+        };
+        self.facts.push(fact.clone());
+
+        Ok(fact)
+    }
+
+    /// Executes a single contract call, dispatching it by entrypoint selector.
+    pub fn execute_call(&mut self, call: &Call) -> Result<(), MockIntegrityError> {
+        if call.selector == SELECTOR_VERIFY_PROOF_INITIAL_CALL {
+            self.verify_proof_initial(
+                &VerifyProofInitialCall::decode(&call.calldata)
+                    .map_err(MockIntegrityError::Decode)?,
+            )
+        } else if call.selector == SELECTOR_VERIFY_PROOF_STEP_CALL {
+            self.verify_proof_step(
+                &VerifyProofStepCall::decode(&call.calldata).map_err(MockIntegrityError::Decode)?,
+            )
+        } else if call.selector == SELECTOR_VERIFY_PROOF_FINAL_AND_REGISTER_FACT_CALL {
+            self.verify_proof_final_and_register_fact(
+                &VerifyProofFinalAndRegisterFactCall::decode(&call.calldata)
+                    .map_err(MockIntegrityError::Decode)?,
+            )
+            .map(|_| ())
+        } else {
+            Err(MockIntegrityError::UnknownSelector(call.selector))
+        }
+    }
+
+    /// Gets how far the job `job_id` has progressed.
+    pub fn job_progress(&self, job_id: Felt) -> JobProgress {
+        match self.jobs.get(&job_id) {
+            Some(job) => JobProgress::InProgress {
+                next_layer: job.fri_state.1.iter,
+            },
+            None if self.facts.iter().any(|fact| fact.job_id == job_id) => JobProgress::Completed,
+            None => JobProgress::NotStarted,
+        }
+    }
+
+    /// Gets all facts registered so far.
+    pub fn registered_facts(&self) -> &[RegisteredFact] {
+        &self.facts
+    }
+}
+
+impl JobState {
+    fn check_fri_state(
+        &self,
+        state_constant: &FriVerificationStateConstant,
+        state_variable: &FriVerificationStateVariable,
+    ) -> Result<(), MockIntegrityError> {
+        let (expected_constant, expected_variable) = &self.fri_state;
+        if expected_constant != state_constant {
+            return Err(MockIntegrityError::StateConstantMismatch);
+        }
+        if expected_variable.iter != state_variable.iter {
+            return Err(MockIntegrityError::IterMismatch {
+                expected: expected_variable.iter,
+                actual: state_variable.iter,
+            });
+        }
+        if expected_variable.queries != state_variable.queries {
+            return Err(MockIntegrityError::QueriesMismatch);
+        }
+
+        Ok(())
+    }
+}
+
+impl MockProvider {
+    /// Creates a new [`MockProvider`] backed by an empty [`MockIntegrity`] instance deployed at
+    /// `contract_address`.
+    pub fn new(contract_address: Felt) -> Self {
+        Self {
+            contract_address,
+            integrity: MockIntegrity::new(),
+            nonces: HashMap::new(),
+            transactions: HashMap::new(),
+        }
+    }
+
+    /// Gets the backing [`MockIntegrity`] instance.
+    pub fn integrity(&self) -> &MockIntegrity {
+        &self.integrity
+    }
+
+    /// Decodes account `__execute__` calldata and executes its calls in order.
+    fn execute(
+        integrity: &mut MockIntegrity,
+        contract_address: Felt,
+        calldata: &[Felt],
+    ) -> Result<(), MockIntegrityError> {
+        let mut iter = calldata.iter();
+        let n_calls = u64::decode_iter(&mut iter).map_err(MockIntegrityError::Decode)?;
+        for _ in 0..n_calls {
+            let call = Call {
+                to: Felt::decode_iter(&mut iter).map_err(MockIntegrityError::Decode)?,
+                selector: Felt::decode_iter(&mut iter).map_err(MockIntegrityError::Decode)?,
+                calldata: Vec::<Felt>::decode_iter(&mut iter)
+                    .map_err(MockIntegrityError::Decode)?,
+            };
+            if call.to != contract_address {
+                return Err(MockIntegrityError::ContractNotDeployed {
+                    address: call.to,
+                    expected: contract_address,
+                });
+            }
+            integrity.execute_call(&call)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl SubmissionProvider for MockProvider {
+    fn nonce(&mut self, address: Felt) -> Result<Felt, ProviderError> {
+        Ok(self.nonces.get(&address).copied().unwrap_or_default())
+    }
+
+    fn add_invoke_transaction(
+        &mut self,
+        transaction: &SignedInvokeTransaction,
+    ) -> Result<(), ProviderError> {
+        let sender_address = transaction.transaction.sender_address;
+        let nonce = self.nonces.entry(sender_address).or_default();
+        if *nonce != transaction.transaction.nonce {
+            return Err(ProviderError::InvalidNonce(transaction.transaction.nonce));
+        }
+        *nonce += Felt::ONE;
+
+        let mut integrity = self.integrity.clone();
+        let status = match Self::execute(
+            &mut integrity,
+            self.contract_address,
+            &transaction.transaction.calldata,
+        ) {
+            Ok(()) => {
+                self.integrity = integrity;
+                TransactionStatus::Accepted
+            }
+            Err(error) => TransactionStatus::Reverted {
+                reason: error.to_string(),
+            },
+        };
+        self.transactions
+            .insert(transaction.transaction_hash, status);
+
+        Ok(())
+    }
+
+    fn transaction_status(
+        &mut self,
+        transaction_hash: Felt,
+    ) -> Result<TransactionStatus, ProviderError> {
+        self.transactions
+            .get(&transaction_hash)
+            .cloned()
+            .ok_or_else(|| {
+                ProviderError::Other(format!("transaction {transaction_hash:#066x} not found"))
+            })
+    }
+
+    fn job_progress(
+        &mut self,
+        contract_address: Felt,
+        job_id: Felt,
+    ) -> Result<JobProgress, ProviderError> {
+        if contract_address != self.contract_address {
+            return Ok(JobProgress::NotStarted);
+        }
+        Ok(self.integrity.job_progress(job_id))
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "recursive",
+    feature = "keccak_160_lsb",
+    feature = "stone5"
+))]
+mod tests {
+    use super::*;
+    use crate::{fact::compute_verification, fixtures};
+
+    #[test]
+    fn registers_fact_of_proof() {
+        let expected =
+            compute_verification(&fixtures::proof(), &fixtures::verifier_config()).unwrap();
+
+        let mut integrity = MockIntegrity::new();
+        let fact = integrity.execute(&fixtures::calls(Felt::ONE)).unwrap();
+
+        assert_eq!(fact.fact, expected.fact);
+        assert_eq!(fact.verification_hash, expected.verification_hash);
+        assert_eq!(fact.security_bits, expected.security_bits);
+        assert_eq!(integrity.registered_facts(), [fact]);
+        assert_eq!(integrity.job_progress(Felt::ONE), JobProgress::Completed);
+    }
+
+    #[test]
+    fn rejects_tampered_layer() {
+        let mut calls = fixtures::calls(Felt::ONE);
+        calls.intermediate_steps[0].witness.leaves[0] += Felt::ONE;
+
+        let mut integrity = MockIntegrity::new();
+        let error = integrity.execute(&calls).unwrap_err();
+
+        assert!(matches!(
+            error,
+            MockIntegrityError::StepFailed { index: 0, error }
+                if matches!(*error, MockIntegrityError::FriLayer(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_calls_to_other_contracts() {
+        let contract_address = Felt::from(0x1234u32);
+        let calls = fixtures::calls(Felt::ONE).collect_calls(Felt::from(0x5678u32));
+        let calldata = crate::pack::execute_calldata(&calls[..1]);
+
+        let mut integrity = MockIntegrity::new();
+        assert!(matches!(
+            MockProvider::execute(&mut integrity, contract_address, &calldata),
+            Err(MockIntegrityError::ContractNotDeployed { address, expected })
+                if address == Felt::from(0x5678u32) && expected == contract_address
+        ));
+        assert_eq!(integrity.job_progress(Felt::ONE), JobProgress::NotStarted);
+    }
+
+    #[test]
+    fn checks_first_step_against_proof() {
+        let mut calls = fixtures::calls(Felt::ONE);
+        calls.intermediate_steps[0].state_variable.queries[0].y_value += Felt::ONE;
+        assert!(matches!(
+            MockIntegrity::new().execute(&calls),
+            Err(MockIntegrityError::StepFailed { index: 0, error })
+                if matches!(*error, MockIntegrityError::QueriesMismatch)
+        ));
+
+        let mut calls = fixtures::calls(Felt::ONE);
+        calls.intermediate_steps[0].state_constant.eval_points[0] += Felt::ONE;
+        assert!(matches!(
+            MockIntegrity::new().execute(&calls),
+            Err(MockIntegrityError::StepFailed { index: 0, error })
+                if matches!(*error, MockIntegrityError::StateConstantMismatch)
+        ));
+    }
+
+    #[test]
+    fn keeps_completed_job_ids_reserved() {
+        let calls = fixtures::calls(Felt::ONE);
+        let mut integrity = MockIntegrity::new();
+        integrity.execute(&calls).unwrap();
+
+        assert!(matches!(
+            integrity.verify_proof_initial(&calls.initial),
+            Err(MockIntegrityError::JobAlreadyExists(job_id)) if job_id == Felt::ONE
+        ));
+    }
+}
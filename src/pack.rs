@@ -0,0 +1,296 @@
+use starknet_core::types::{Call, Felt};
+
+use crate::IntegrityCalls;
+
+/// Number of felts an account's `__execute__` calldata spends on the call count.
+const MULTICALL_HEADER_LEN: usize = 1;
+
+/// Number of felts an account's `__execute__` calldata spends on each call besides its own
+/// calldata: `to`, `selector` and the calldata length prefix.
+const MULTICALL_CALL_OVERHEAD: usize = 3;
+
+/// Resource limits that a single transaction must stay within.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionLimits {
+    /// Maximum length of the account `__execute__` calldata, in felts.
+    pub max_calldata_len: usize,
+    /// Maximum number of Cairo steps a transaction may consume.
+    pub max_steps: u64,
+}
+
+/// Estimates the number of Cairo steps a call consumes when executed.
+pub trait StepEstimator {
+    /// Estimates the number of Cairo steps consumed by `call`.
+    fn estimate_steps(&self, call: &Call) -> u64;
+}
+
+/// A [`StepEstimator`] assuming the steps consumed grow linearly with the calldata length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinearStepEstimator {
+    /// Steps consumed by a call regardless of its calldata.
+    pub base_steps: u64,
+    /// Steps consumed per calldata felt.
+    pub steps_per_felt: u64,
+}
+
+/// Groups consecutive calls into multicall transactions that stay within [`TransactionLimits`].
+#[derive(Debug, Clone)]
+pub struct TransactionPacker<E> {
+    limits: TransactionLimits,
+    estimator: E,
+}
+
+/// A multicall transaction produced by [`TransactionPacker`].
+#[derive(Debug, Clone)]
+pub struct PackedTransaction {
+    /// Calls to be executed in this transaction, in order.
+    pub calls: Vec<Call>,
+    /// Length of the account `__execute__` calldata for the calls.
+    pub calldata_len: usize,
+    /// Estimated number of Cairo steps consumed by the calls.
+    pub estimated_steps: u64,
+}
+
+/// Errors from packing calls into transactions.
+#[derive(Debug, thiserror::Error)]
+pub enum PackError {
+    #[error(
+        "call {index} alone takes {calldata_len} calldata felts, exceeding the limit of \
+        {max_calldata_len}"
+    )]
+    CallTooLarge {
+        index: usize,
+        calldata_len: usize,
+        max_calldata_len: usize,
+    },
+    #[error("call {index} alone takes {steps} steps, exceeding the limit of {max_steps}")]
+    CallExceedsStepBudget {
+        index: usize,
+        steps: u64,
+        max_steps: u64,
+    },
+}
+
+/// Computes the length of the account `__execute__` calldata of a transaction with a single call.
+pub(crate) fn single_call_transaction_calldata_len(call: &Call) -> usize {
+    MULTICALL_HEADER_LEN + MULTICALL_CALL_OVERHEAD + call.calldata.len()
+}
+
+/// Encodes calls into the account `__execute__` calldata of a multicall transaction.
+pub(crate) fn execute_calldata(calls: &[Call]) -> Vec<Felt> {
+    let mut calldata = vec![calls.len().into()];
+    for call in calls {
+        calldata.push(call.to);
+        calldata.push(call.selector);
+        calldata.push(call.calldata.len().into());
+        calldata.extend_from_slice(&call.calldata);
+    }
+    calldata
+}
+
+impl<F> StepEstimator for F
+where
+    F: Fn(&Call) -> u64,
+{
+    fn estimate_steps(&self, call: &Call) -> u64 {
+        self(call)
+    }
+}
+
+impl StepEstimator for LinearStepEstimator {
+    fn estimate_steps(&self, call: &Call) -> u64 {
+        self.base_steps.saturating_add(
+            self.steps_per_felt
+                .saturating_mul(call.calldata.len() as u64),
+        )
+    }
+}
+
+impl<E> TransactionPacker<E>
+where
+    E: StepEstimator,
+{
+    /// Creates a new [`TransactionPacker`] with the given limits and step estimator.
+    pub fn new(limits: TransactionLimits, estimator: E) -> Self {
+        Self { limits, estimator }
+    }
+
+    /// Packs all calls of a verification job into transactions against the `integrity` contract
+    /// at `contract_address`.
+    pub fn pack(
+        &self,
+        calls: IntegrityCalls,
+        contract_address: Felt,
+    ) -> Result<Vec<PackedTransaction>, PackError> {
+        self.pack_calls(calls.collect_calls(contract_address))
+    }
+
+    /// Packs calls into transactions, greedily filling each transaction while preserving the call
+    /// order.
+    pub fn pack_calls(&self, calls: Vec<Call>) -> Result<Vec<PackedTransaction>, PackError> {
+        let mut transactions = vec![];
+        let mut current: Option<PackedTransaction> = None;
+
+        for (index, call) in calls.into_iter().enumerate() {
+            let calldata_len = MULTICALL_CALL_OVERHEAD + call.calldata.len();
+            let steps = self.estimator.estimate_steps(&call);
+
+            if MULTICALL_HEADER_LEN + calldata_len > self.limits.max_calldata_len {
+                return Err(PackError::CallTooLarge {
+                    index,
+                    calldata_len: MULTICALL_HEADER_LEN + calldata_len,
+                    max_calldata_len: self.limits.max_calldata_len,
+                });
+            }
+            if steps > self.limits.max_steps {
+                return Err(PackError::CallExceedsStepBudget {
+                    index,
+                    steps,
+                    max_steps: self.limits.max_steps,
+                });
+            }
+
+            match current.as_mut() {
+                Some(transaction)
+                    if transaction.calldata_len + calldata_len <= self.limits.max_calldata_len
+                        && transaction
+                            .estimated_steps
+                            .checked_add(steps)
+                            .is_some_and(|total| total <= self.limits.max_steps) =>
+                {
+                    transaction.calls.push(call);
+                    transaction.calldata_len += calldata_len;
+                    transaction.estimated_steps += steps;
+                }
+                _ => {
+                    transactions.extend(current.take());
+                    current = Some(PackedTransaction {
+                        calls: vec![call],
+                        calldata_len: MULTICALL_HEADER_LEN + calldata_len,
+                        estimated_steps: steps,
+                    });
+                }
+            }
+        }
+
+        transactions.extend(current);
+        Ok(transactions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(id: u64, calldata_len: usize) -> Call {
+        Call {
+            to: Felt::ONE,
+            selector: Felt::from(id),
+            calldata: vec![Felt::ZERO; calldata_len],
+        }
+    }
+
+    fn packer(max_calldata_len: usize, max_steps: u64) -> TransactionPacker<LinearStepEstimator> {
+        TransactionPacker::new(
+            TransactionLimits {
+                max_calldata_len,
+                max_steps,
+            },
+            LinearStepEstimator {
+                base_steps: 0,
+                steps_per_felt: 1,
+            },
+        )
+    }
+
+    fn selectors(transactions: &[PackedTransaction]) -> Vec<Vec<Felt>> {
+        transactions
+            .iter()
+            .map(|transaction| transaction.calls.iter().map(|call| call.selector).collect())
+            .collect()
+    }
+
+    #[test]
+    fn preserves_call_order() {
+        let calls = (0..5).map(|id| call(id, 2)).collect::<Vec<_>>();
+        let transactions = packer(1 + 2 * 5, u64::MAX).pack_calls(calls).unwrap();
+
+        assert_eq!(
+            selectors(&transactions),
+            [
+                vec![0u64.into(), 1u64.into()],
+                vec![2u64.into(), 3u64.into()],
+                vec![4u64.into()]
+            ]
+        );
+        for transaction in &transactions {
+            assert_eq!(
+                transaction.calldata_len,
+                execute_calldata(&transaction.calls).len()
+            );
+        }
+    }
+
+    #[test]
+    fn stops_at_calldata_limit() {
+        // Each call takes 5 felts, so 2 calls fit in 11 felts but 3 don't.
+        let calls = (0..3).map(|id| call(id, 2)).collect::<Vec<_>>();
+        let transactions = packer(11, u64::MAX).pack_calls(calls).unwrap();
+
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].calls.len(), 2);
+        assert_eq!(transactions[0].calldata_len, 11);
+    }
+
+    #[test]
+    fn stops_at_step_limit() {
+        let calls = (0..3).map(|id| call(id, 2)).collect::<Vec<_>>();
+        let transactions = packer(usize::MAX, 5).pack_calls(calls).unwrap();
+
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].estimated_steps, 4);
+        assert_eq!(transactions[1].estimated_steps, 2);
+    }
+
+    #[test]
+    fn rejects_calls_over_the_limits() {
+        let calls = vec![call(0, 1), call(1, 8)];
+        assert!(matches!(
+            packer(11, u64::MAX).pack_calls(calls.clone()),
+            Err(PackError::CallTooLarge {
+                index: 1,
+                calldata_len: 12,
+                max_calldata_len: 11,
+            })
+        ));
+        assert!(matches!(
+            packer(usize::MAX, 7).pack_calls(calls),
+            Err(PackError::CallExceedsStepBudget {
+                index: 1,
+                steps: 8,
+                max_steps: 7,
+            })
+        ));
+    }
+
+    #[test]
+    fn does_not_overflow_step_budget() {
+        let estimator = |call: &Call| {
+            if call.selector == Felt::ZERO {
+                u64::MAX
+            } else {
+                1
+            }
+        };
+        let packer = TransactionPacker::new(
+            TransactionLimits {
+                max_calldata_len: usize::MAX,
+                max_steps: u64::MAX,
+            },
+            estimator,
+        );
+        let transactions = packer.pack_calls(vec![call(0, 0), call(1, 0)]).unwrap();
+
+        assert_eq!(transactions.len(), 2);
+    }
+}
@@ -0,0 +1,366 @@
+use starknet_core::types::Felt;
+use starknet_crypto::poseidon_hash_many;
+
+use crate::{
+    bindings::{FriLayerWitness, FriVerificationStateConstant, FriVerificationStateVariable},
+    config::{ConfigError, Hasher},
+    crypto::DecommitError,
+    error::Error,
+    fri::{verify_last_layer, verify_layer, FriLayerError, LastLayerError},
+    split::{MaterializedSplitProof, SplitProof, VerifyProofStepParam},
+    IntegrityCalls,
+};
+
+/// Failures found by a preflight check, located by the index of the intermediate step they occur
+/// in.
+///
+/// Merkle tree nodes are identified by their heap-like index, where the root is `1` and the
+/// children of node `i` are `2i` and `2i + 1`. Note that a Merkle root mismatch can't be attributed
+/// to a single query or authentication node, as a wrong value anywhere on the paths changes the
+/// root.
+#[derive(Debug, thiserror::Error)]
+pub enum PreflightError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Split(#[from] Error),
+    #[error("step {step}: FRI state constant differs from the initial one")]
+    StateConstantMismatch { step: usize },
+    #[error("step {step}: expected FRI layer {expected}, got {actual}")]
+    IterMismatch {
+        step: usize,
+        expected: u32,
+        actual: u32,
+    },
+    #[error("step {step}: FRI queries differ from the ones produced by the previous step")]
+    QueriesMismatch { step: usize },
+    #[error("step {step}: {error}")]
+    InvalidLayer {
+        step: usize,
+        #[source]
+        error: FriLayerError,
+    },
+    #[error("step {step}: {error}")]
+    InvalidDecommitment {
+        step: usize,
+        #[source]
+        error: DecommitError,
+    },
+    #[error(
+        "step {step}: authentication node {auth_index} for tree node {node:#x} on the path of \
+        query {query_index:#x} is missing"
+    )]
+    MissingAuthentication {
+        step: usize,
+        query_index: Felt,
+        auth_index: usize,
+        node: Felt,
+    },
+    #[error(
+        "step {step}: Merkle root {computed:#x} computed from the witness does not match the layer \
+        commitment {expected:#x}"
+    )]
+    RootMismatch {
+        step: usize,
+        expected: Felt,
+        computed: Felt,
+    },
+    #[error("final step: expected FRI layer {expected}, got {actual}")]
+    FinalIterMismatch { expected: u32, actual: u32 },
+    #[error("final step: FRI queries differ from the ones produced by the last intermediate step")]
+    FinalQueriesMismatch,
+    #[error(
+        "final step: last layer coefficients hash to {computed:#x} instead of the committed \
+        {expected:#x}"
+    )]
+    LastLayerCoefficientsHashMismatch { expected: Felt, computed: Felt },
+    #[error("final step: last layer verification failed: {0}")]
+    LastLayer(#[source] LastLayerError),
+}
+
+/// Checks the FRI layer witnesses and last layer coefficients of `calls` the same way the
+/// `integrity` verifier contract does, so that a bad witness is caught before submission.
+///
+/// Each intermediate step is checked against the layer commitment in the state constant by
+/// recomputing the Merkle root from its authentication paths, and must carry the state produced by
+/// the previous step. The last layer coefficients must match the committed hash.
+pub fn preflight(calls: &IntegrityCalls) -> Result<(), PreflightError> {
+    let hasher = calls.initial.verifier_config.hasher()?;
+    let state_constant = &calls.final_step.state_constant;
+
+    let mut preflight = Preflight::new(hasher, state_constant);
+    for (step, call) in calls.intermediate_steps.iter().enumerate() {
+        if call.state_constant != *state_constant {
+            return Err(PreflightError::StateConstantMismatch { step });
+        }
+        preflight.check_step(&call.state_variable, &call.witness)?;
+    }
+    preflight.check_final(
+        &calls.final_step.state_variable,
+        &calls.final_step.last_layer_coefficients,
+    )
+}
+
+impl SplitProof {
+    /// Generates all verification steps and checks them with [`preflight`], leaving the split
+    /// proof untouched.
+    pub fn preflight(&self) -> Result<(), PreflightError> {
+        let hasher = self.verifier_config.hasher()?;
+
+        let mut preflight = Preflight::new(hasher, &self.state_const);
+        for step in self.step_iter.clone() {
+            match step? {
+                VerifyProofStepParam::Intermediate(step) => {
+                    preflight.check_step(&step.state_variable, &step.witness)?
+                }
+                VerifyProofStepParam::Final(final_step) => {
+                    return preflight.check_final(
+                        &final_step.state_variable,
+                        &final_step.last_layer_coefficients,
+                    )
+                }
+            }
+        }
+
+        Err(Error::StepsExhausted.into())
+    }
+}
+
+impl MaterializedSplitProof {
+    /// Checks all verification steps with [`preflight`].
+    pub fn preflight(&self) -> Result<(), PreflightError> {
+        let hasher = self.verifier_config.hasher()?;
+
+        let mut preflight = Preflight::new(hasher, &self.state_const);
+        for step in self.steps.iter() {
+            preflight.check_step(&step.state_variable, &step.witness)?;
+        }
+        preflight.check_final(&self.final_state_variable, &self.last_layer_coefficients)
+    }
+}
+
+/// Running state of a preflight check over consecutive steps.
+struct Preflight<'a> {
+    hasher: Hasher,
+    state_constant: &'a FriVerificationStateConstant,
+    step: usize,
+    /// The state variable produced by the previous step, if any.
+    expected_state: Option<FriVerificationStateVariable>,
+}
+
+impl<'a> Preflight<'a> {
+    fn new(hasher: Hasher, state_constant: &'a FriVerificationStateConstant) -> Self {
+        Self {
+            hasher,
+            state_constant,
+            step: 0,
+            expected_state: None,
+        }
+    }
+
+    fn check_step(
+        &mut self,
+        state_variable: &FriVerificationStateVariable,
+        witness: &FriLayerWitness,
+    ) -> Result<(), PreflightError> {
+        let step = self.step;
+        let expected_iter = step as u32;
+        if state_variable.iter != expected_iter {
+            return Err(PreflightError::IterMismatch {
+                step,
+                expected: expected_iter,
+                actual: state_variable.iter,
+            });
+        }
+        if self
+            .expected_state
+            .as_ref()
+            .is_some_and(|expected| expected.queries != state_variable.queries)
+        {
+            return Err(PreflightError::QueriesMismatch { step });
+        }
+
+        let next_state = verify_layer(self.hasher, self.state_constant, state_variable, witness)
+            .map_err(|error| match error {
+                FriLayerError::Config(err) => PreflightError::Config(err),
+                FriLayerError::Decommitment { error, .. } => match error {
+                    DecommitError::Config(err) => PreflightError::Config(err),
+                    DecommitError::MissingAuthentication { index, node, query } => {
+                        PreflightError::MissingAuthentication {
+                            step,
+                            query_index: query,
+                            auth_index: index,
+                            node,
+                        }
+                    }
+                    DecommitError::RootMismatch { expected, actual } => {
+                        PreflightError::RootMismatch {
+                            step,
+                            expected,
+                            computed: actual,
+                        }
+                    }
+                    error => PreflightError::InvalidDecommitment { step, error },
+                },
+                error => PreflightError::InvalidLayer { step, error },
+            })?;
+
+        self.step += 1;
+        self.expected_state = Some(next_state);
+
+        Ok(())
+    }
+
+    fn check_final(
+        self,
+        state_variable: &FriVerificationStateVariable,
+        last_layer_coefficients: &[Felt],
+    ) -> Result<(), PreflightError> {
+        if state_variable.iter != self.state_constant.n_layers {
+            return Err(PreflightError::FinalIterMismatch {
+                expected: self.state_constant.n_layers,
+                actual: state_variable.iter,
+            });
+        }
+        if self
+            .expected_state
+            .is_some_and(|expected| expected.queries != state_variable.queries)
+        {
+            return Err(PreflightError::FinalQueriesMismatch);
+        }
+
+        let computed = poseidon_hash_many(last_layer_coefficients);
+        if computed != self.state_constant.last_layer_coefficients_hash {
+            return Err(PreflightError::LastLayerCoefficientsHashMismatch {
+                expected: self.state_constant.last_layer_coefficients_hash,
+                computed,
+            });
+        }
+
+        verify_last_layer(&state_variable.queries, last_layer_coefficients)
+            .map_err(PreflightError::LastLayer)
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "recursive",
+    feature = "keccak_160_lsb",
+    feature = "stone5"
+))]
+mod tests {
+    use starknet_core::types::NonZeroFelt;
+
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn accepts_fixture() {
+        preflight(&fixtures::calls(Felt::ONE)).unwrap();
+    }
+
+    #[test]
+    fn locates_missing_authentication() {
+        let mut calls = fixtures::calls(Felt::ONE);
+        let step = &mut calls.intermediate_steps[1];
+        let authentications = &mut step.witness.table_witness.vector.authentications;
+        authentications.pop();
+        let n_authentications = authentications.len();
+
+        let Err(PreflightError::MissingAuthentication {
+            step: 1,
+            query_index,
+            auth_index,
+            node,
+        }) = preflight(&calls)
+        else {
+            panic!("expected a missing authentication in step 1");
+        };
+        assert_eq!(auth_index, n_authentications);
+
+        // Table rows hold whole cosets, so the query must be the coset of one of the step's queries,
+        // and the node must be the sibling of a node on its path.
+        let step = &calls.intermediate_steps[1];
+        let coset_size = Felt::TWO.pow_felt(&step.state_constant.step_sizes[1]);
+        let coset = |index: Felt| index.floor_div(&NonZeroFelt::try_from(coset_size).unwrap());
+        assert!(step
+            .state_variable
+            .queries
+            .iter()
+            .any(|query| coset(query.index) == query_index));
+
+        let height = step.state_constant.commitment[1]
+            .vector_commitment
+            .config
+            .height;
+        let mut path_node = query_index + Felt::TWO.pow_felt(&height);
+        let mut on_path = false;
+        while path_node > Felt::ONE {
+            let (parent, bit) = path_node.div_rem(&NonZeroFelt::TWO);
+            let sibling = if bit == Felt::ZERO {
+                path_node + Felt::ONE
+            } else {
+                path_node - Felt::ONE
+            };
+            on_path |= sibling == node;
+            path_node = parent;
+        }
+        assert!(
+            on_path,
+            "node {node:#x} is not on the path of {query_index:#x}"
+        );
+    }
+
+    #[test]
+    fn reports_root_mismatch_of_tampered_step() {
+        let mut calls = fixtures::calls(Felt::ONE);
+        calls.intermediate_steps[2]
+            .witness
+            .table_witness
+            .vector
+            .authentications[0] += Felt::ONE;
+
+        let commitment = calls.final_step.state_constant.commitment[2]
+            .vector_commitment
+            .commitment_hash;
+        assert!(matches!(
+            preflight(&calls),
+            Err(PreflightError::RootMismatch { step: 2, expected, .. }) if expected == commitment
+        ));
+    }
+
+    #[test]
+    fn reports_invalid_layer() {
+        let mut calls = fixtures::calls(Felt::ONE);
+        calls.intermediate_steps[1].witness.leaves.pop();
+
+        assert!(matches!(
+            preflight(&calls),
+            Err(PreflightError::InvalidLayer {
+                step: 1,
+                error: FriLayerError::LeafCountMismatch { layer: 1, .. },
+            })
+        ));
+    }
+
+    #[test]
+    fn locates_last_layer_mismatch() {
+        let mut calls = fixtures::calls(Felt::ONE);
+        calls.final_step.last_layer_coefficients[0] += Felt::ONE;
+        let hash = poseidon_hash_many(&calls.final_step.last_layer_coefficients);
+        calls.final_step.state_constant.last_layer_coefficients_hash = hash;
+        for step in &mut calls.intermediate_steps {
+            step.state_constant.last_layer_coefficients_hash = hash;
+        }
+
+        let first_query = &calls.final_step.state_variable.queries[0];
+        assert!(matches!(
+            preflight(&calls),
+            Err(PreflightError::LastLayer(LastLayerError::QueryMismatch {
+                index,
+                expected,
+                ..
+            })) if index == first_query.index && expected == first_query.y_value
+        ));
+    }
+}
@@ -0,0 +1,370 @@
+use std::{
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use starknet_core::{
+    codec::{Decode, Encode, Error as CodecError},
+    types::Felt,
+};
+
+use crate::{
+    bindings::{
+        VerifierConfiguration, VerifyProofFinalAndRegisterFactCall, VerifyProofInitialCall,
+        VerifyProofStepCall,
+    },
+    source_map::CalldataSourceMap,
+    IntegrityCalls,
+};
+
+/// Name of the file holding the `verify_proof_initial` calldata.
+const INITIAL_FILE_NAME: &str = "initial";
+/// Name prefix of the files holding the `verify_proof_step` calldata, numbered from 1.
+const STEP_FILE_PREFIX: &str = "step";
+/// Name of the file holding the `verify_proof_final_and_register_fact` calldata.
+const FINAL_FILE_NAME: &str = "final";
+/// Number of felts left out of the `verify_proof_initial` calldata: the job ID and the 4-felt
+/// verifier configuration.
+const INITIAL_PREFIX_LEN: usize = 5;
+
+/// Calldata in the format written by the `integrity` reference proof serializer.
+///
+/// Each call is stored as whitespace-separated decimal felts in its own file: `initial`, `step1`
+/// to `stepN` and `final`. The job ID, and for the initial call the verifier configuration, are
+/// left out as they're passed separately when invoking the contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceCalldata {
+    /// Calldata of `verify_proof_initial`, without the job ID and verifier configuration.
+    pub initial: Vec<Felt>,
+    /// Calldata of each `verify_proof_step`, without the job ID.
+    pub steps: Vec<Vec<Felt>>,
+    /// Calldata of `verify_proof_final_and_register_fact`, without the job ID.
+    pub final_step: Vec<Felt>,
+}
+
+/// The first felt that differs between [`ReferenceCalldata`] and local [`IntegrityCalls`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalldataDiff {
+    /// Name of the reference file the difference is in, e.g. `step3`.
+    pub file: String,
+    /// Offset of the differing felt within the file.
+    pub offset: usize,
+    /// Path of the local field at `offset`, or `None` if the local calldata is shorter.
+    pub path: Option<String>,
+    /// The reference felt, or `None` if the reference calldata is shorter.
+    pub reference: Option<Felt>,
+    /// The local felt, or `None` if the local calldata is shorter.
+    pub local: Option<Felt>,
+}
+
+/// Errors from reading calldata in the reference serializer format.
+#[derive(Debug, thiserror::Error)]
+pub enum ReferenceError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("reference calldata file {0} not found")]
+    MissingFile(PathBuf),
+    #[error("invalid felt `{token}` at {}:{line}", path.display())]
+    InvalidFelt {
+        path: PathBuf,
+        line: usize,
+        token: String,
+    },
+    #[error("failed to decode `{file}` calldata: {error}")]
+    Decode { file: String, error: CodecError },
+    #[error("`{file}` calldata has {count} trailing felts")]
+    TrailingFelts { file: String, count: usize },
+}
+
+impl ReferenceCalldata {
+    /// Converts [`IntegrityCalls`] into the reference format.
+    pub fn from_calls(calls: &IntegrityCalls) -> Self {
+        Self {
+            initial: calls.initial.reference_calldata(),
+            steps: calls
+                .intermediate_steps
+                .iter()
+                .map(|step| step.reference_calldata())
+                .collect(),
+            final_step: calls.final_step.reference_calldata(),
+        }
+    }
+
+    /// Decodes the reference calldata into [`IntegrityCalls`], filling in the job ID and verifier
+    /// configuration left out by the format.
+    pub fn to_calls(
+        &self,
+        job_id: Felt,
+        verifier_config: VerifierConfiguration,
+    ) -> Result<IntegrityCalls, ReferenceError> {
+        let mut initial_prefix = vec![job_id];
+        // This type never fails to serialize
+        verifier_config.encode(&mut initial_prefix).unwrap();
+
+        let initial = decode_file(INITIAL_FILE_NAME, &initial_prefix, &self.initial)?;
+        let intermediate_steps = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| decode_file(&step_file_name(index), &[job_id], step))
+            .collect::<Result<_, _>>()?;
+        let final_step = decode_file(FINAL_FILE_NAME, &[job_id], &self.final_step)?;
+
+        Ok(IntegrityCalls {
+            initial,
+            intermediate_steps,
+            final_step,
+        })
+    }
+
+    /// Reads the calldata files from `dir`, taking step files until the first missing number.
+    pub fn read_dir<P: AsRef<Path>>(dir: P) -> Result<Self, ReferenceError> {
+        let dir = dir.as_ref();
+
+        let initial = read_file(&dir.join(INITIAL_FILE_NAME))?;
+        let mut steps = vec![];
+        loop {
+            let path = dir.join(step_file_name(steps.len()));
+            if !path.exists() {
+                break;
+            }
+            steps.push(read_file(&path)?);
+        }
+        let final_step = read_file(&dir.join(FINAL_FILE_NAME))?;
+
+        Ok(Self {
+            initial,
+            steps,
+            final_step,
+        })
+    }
+
+    /// Writes the calldata files into `dir`, which must already exist.
+    pub fn write_dir<P: AsRef<Path>>(&self, dir: P) -> Result<(), ReferenceError> {
+        let dir = dir.as_ref();
+
+        fs::write(dir.join(INITIAL_FILE_NAME), format_felts(&self.initial))?;
+        for (index, step) in self.steps.iter().enumerate() {
+            fs::write(dir.join(step_file_name(index)), format_felts(step))?;
+        }
+        fs::write(dir.join(FINAL_FILE_NAME), format_felts(&self.final_step))?;
+
+        Ok(())
+    }
+
+    /// Finds the first felt that differs from the calldata of `calls`, in file order.
+    ///
+    /// Useful for cross-checking this crate's encoding against the reference serializer on the
+    /// same proof.
+    pub fn compare(&self, calls: &IntegrityCalls) -> Option<CalldataDiff> {
+        if let Some(diff) = compare_file(
+            INITIAL_FILE_NAME,
+            &self.initial,
+            &calls.initial.calldata_source_map(),
+            INITIAL_PREFIX_LEN,
+        ) {
+            return Some(diff);
+        }
+
+        let empty = CalldataSourceMap::default();
+        let n_steps = self.steps.len().max(calls.intermediate_steps.len());
+        for index in 0..n_steps {
+            let local = calls
+                .intermediate_steps
+                .get(index)
+                .map(|step| step.calldata_source_map());
+            let diff = compare_file(
+                &step_file_name(index),
+                self.steps.get(index).map_or(&[], |step| step.as_slice()),
+                local.as_ref().unwrap_or(&empty),
+                1,
+            );
+            if diff.is_some() {
+                return diff;
+            }
+        }
+
+        compare_file(
+            FINAL_FILE_NAME,
+            &self.final_step,
+            &calls.final_step.calldata_source_map(),
+            1,
+        )
+    }
+}
+
+/// Formats felts as space-separated decimals, as written by the reference serializer.
+pub fn format_felts(felts: &[Felt]) -> String {
+    let mut text = String::new();
+    for (index, felt) in felts.iter().enumerate() {
+        if index != 0 {
+            text.push(' ');
+        }
+        write!(text, "{felt}").unwrap();
+    }
+    text
+}
+
+fn step_file_name(index: usize) -> String {
+    format!("{STEP_FILE_PREFIX}{}", index + 1)
+}
+
+fn read_file(path: &Path) -> Result<Vec<Felt>, ReferenceError> {
+    if !path.exists() {
+        return Err(ReferenceError::MissingFile(path.to_owned()));
+    }
+    parse_felts(path, &fs::read_to_string(path)?)
+}
+
+/// Parses whitespace-separated felts read from `path`, accepting both decimal and `0x`-prefixed
+/// hexadecimal.
+fn parse_felts(path: &Path, text: &str) -> Result<Vec<Felt>, ReferenceError> {
+    text.lines()
+        .enumerate()
+        .flat_map(|(index, line)| line.split_whitespace().map(move |token| (index + 1, token)))
+        .map(|(line, token)| {
+            Felt::from_str(token).map_err(|_| ReferenceError::InvalidFelt {
+                path: path.to_owned(),
+                line,
+                token: token.to_owned(),
+            })
+        })
+        .collect()
+}
+
+fn decode_file<T>(file: &str, prefix: &[Felt], felts: &[Felt]) -> Result<T, ReferenceError>
+where
+    T: for<'a> Decode<'a>,
+{
+    let mut iter = prefix.iter().chain(felts.iter());
+    let value = T::decode_iter(&mut iter).map_err(|error| ReferenceError::Decode {
+        file: file.to_owned(),
+        error,
+    })?;
+
+    let count = iter.count();
+    if count != 0 {
+        return Err(ReferenceError::TrailingFelts {
+            file: file.to_owned(),
+            count,
+        });
+    }
+
+    Ok(value)
+}
+
+/// Compares a reference file with local calldata, skipping the first `skip` local felts that the
+/// reference format leaves out.
+fn compare_file(
+    file: &str,
+    reference: &[Felt],
+    local: &CalldataSourceMap,
+    skip: usize,
+) -> Option<CalldataDiff> {
+    let local = local.entries().get(skip..).unwrap_or_default();
+
+    (0..reference.len().max(local.len())).find_map(|offset| {
+        let reference = reference.get(offset).copied();
+        let entry = local.get(offset);
+        if reference == entry.map(|entry| entry.value) {
+            return None;
+        }
+
+        Some(CalldataDiff {
+            file: file.to_owned(),
+            offset,
+            path: entry.map(|entry| entry.path.clone()),
+            reference,
+            local: entry.map(|entry| entry.value),
+        })
+    })
+}
+
+impl VerifyProofInitialCall {
+    /// Encodes the calldata in the reference serializer format, leaving out the job ID and
+    /// verifier configuration.
+    pub fn reference_calldata(&self) -> Vec<Felt> {
+        self.calldata()[INITIAL_PREFIX_LEN..].to_vec()
+    }
+}
+
+impl VerifyProofStepCall {
+    /// Encodes the calldata in the reference serializer format, leaving out the job ID.
+    pub fn reference_calldata(&self) -> Vec<Felt> {
+        self.calldata()[1..].to_vec()
+    }
+}
+
+impl VerifyProofFinalAndRegisterFactCall {
+    /// Encodes the calldata in the reference serializer format, leaving out the job ID.
+    pub fn reference_calldata(&self) -> Vec<Felt> {
+        self.calldata()[1..].to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(all(feature = "recursive", feature = "keccak_160_lsb", feature = "stone5"))]
+    use crate::fixtures;
+
+    #[cfg(all(feature = "recursive", feature = "keccak_160_lsb", feature = "stone5"))]
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("integrity-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[cfg(all(feature = "recursive", feature = "keccak_160_lsb", feature = "stone5"))]
+    #[test]
+    fn write_read_round_trip() {
+        let job_id = Felt::from(42);
+        let calls = fixtures::calls(job_id);
+        let dir = temp_dir("reference-round-trip");
+
+        ReferenceCalldata::from_calls(&calls)
+            .write_dir(&dir)
+            .unwrap();
+        let reference = ReferenceCalldata::read_dir(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(reference.steps.len(), calls.intermediate_steps.len());
+        assert_eq!(reference.compare(&calls), None);
+        let decoded = reference
+            .to_calls(job_id, fixtures::verifier_config())
+            .unwrap();
+        assert_eq!(decoded.initial, calls.initial);
+        assert_eq!(decoded.intermediate_steps, calls.intermediate_steps);
+        assert_eq!(decoded.final_step, calls.final_step);
+    }
+
+    /// Checks interoperability with calldata written by the `integrity` reference proof serializer
+    /// for the fixture proof into `test-data/recursive_keccak_160_lsb_stone5_reference`, which
+    /// isn't committed yet.
+    #[cfg(all(feature = "recursive", feature = "keccak_160_lsb", feature = "stone5"))]
+    #[test]
+    #[ignore = "needs calldata from the reference serializer"]
+    fn matches_reference_serializer() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("test-data/recursive_keccak_160_lsb_stone5_reference");
+        let reference = ReferenceCalldata::read_dir(&dir).unwrap();
+
+        assert_eq!(reference.compare(&fixtures::calls(Felt::ONE)), None);
+    }
+
+    #[test]
+    fn invalid_felt_location() {
+        let path = Path::new("calldata/step1");
+        let error = parse_felts(path, "1 0x2\n3 four 5\n").unwrap_err();
+
+        assert!(matches!(
+            &error,
+            ReferenceError::InvalidFelt { path: error_path, line: 2, token }
+                if error_path == path && token == "four"
+        ));
+        assert_eq!(error.to_string(), "invalid felt `four` at calldata/step1:2");
+    }
+}
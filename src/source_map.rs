@@ -0,0 +1,449 @@
+use starknet_core::types::Felt;
+
+use crate::bindings::{
+    AddrValue, ContinuousPageHeader, FriConfigWithSerde, FriLayerQuery, FriLayerWitness,
+    FriUnsentCommitmentWithSerde, FriVerificationStateConstant, FriVerificationStateVariable,
+    FriWitnessWithSerde, ProofOfWorkConfigWithSerde, ProofOfWorkUnsentCommitmentWithSerde,
+    PublicInputWithSerde, SegmentInfo, StarkConfigWithSerde, StarkProofWithSerde,
+    StarkUnsentCommitmentWithSerde, StarkWitnessWithSerde, TableCommitment, TableCommitmentConfig,
+    TableCommitmentConfigWithSerde, TableCommitmentWitness, TableCommitmentWitnessWithSerde,
+    TableDecommitmentWithSerde, TracesConfigWithSerde, TracesDecommitmentWithSerde,
+    TracesUnsentCommitmentWithSerde, TracesWitnessWithSerde, VectorCommitment,
+    VectorCommitmentConfig, VectorCommitmentConfigWithSerde, VectorCommitmentWitness,
+    VectorCommitmentWitnessWithSerde, VerifierConfiguration, VerifyProofFinalAndRegisterFactCall,
+    VerifyProofFullAndRegisterFactCall, VerifyProofInitialCall, VerifyProofStepCall,
+};
+
+/// Encoded calldata of a contract call, recording the field each felt was written for.
+///
+/// Paths use `.` for struct fields and `[i]` for list elements, e.g.
+/// `stark_proof.witness.traces_decommitment.original.values[12]`. Length prefixes are recorded as
+/// `.len` for regular lists, while the extra prefixes of the canonical Cairo types use their Cairo
+/// field names, e.g. `stark_proof.public_input.n_segments`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CalldataSourceMap {
+    entries: Vec<AnnotatedFelt>,
+}
+
+/// A single calldata felt along with the path of the field it was written for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnnotatedFelt {
+    /// The encoded felt.
+    pub value: Felt,
+    /// Path of the field the felt belongs to.
+    pub path: String,
+}
+
+/// Encoding that records the source field of each felt, mirroring the type's `Encode`
+/// implementation.
+pub(crate) trait EncodeAnnotated {
+    fn encode_annotated(&self, path: &str, map: &mut CalldataSourceMap);
+}
+
+impl CalldataSourceMap {
+    /// Gets the annotated felts in calldata order.
+    pub fn entries(&self) -> &[AnnotatedFelt] {
+        &self.entries
+    }
+
+    /// Gets the number of calldata felts.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the calldata is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Gets the path of the field at calldata `offset`, if it's in range.
+    pub fn path_at(&self, offset: usize) -> Option<&str> {
+        self.entries.get(offset).map(|entry| entry.path.as_str())
+    }
+
+    /// Gets the plain calldata, identical to the call's regular encoding.
+    pub fn calldata(&self) -> Vec<Felt> {
+        self.entries.iter().map(|entry| entry.value).collect()
+    }
+
+    fn write(&mut self, path: String, value: Felt) {
+        self.entries.push(AnnotatedFelt { value, path });
+    }
+
+    fn build<T: EncodeAnnotated>(value: &T) -> Self {
+        let mut map = Self::default();
+        value.encode_annotated("", &mut map);
+        map
+    }
+}
+
+impl VerifyProofInitialCall {
+    /// Encodes the calldata while recording the source field of each felt.
+    pub fn calldata_source_map(&self) -> CalldataSourceMap {
+        CalldataSourceMap::build(self)
+    }
+}
+
+impl VerifyProofStepCall {
+    /// Encodes the calldata while recording the source field of each felt.
+    pub fn calldata_source_map(&self) -> CalldataSourceMap {
+        CalldataSourceMap::build(self)
+    }
+}
+
+impl VerifyProofFinalAndRegisterFactCall {
+    /// Encodes the calldata while recording the source field of each felt.
+    pub fn calldata_source_map(&self) -> CalldataSourceMap {
+        CalldataSourceMap::build(self)
+    }
+}
+
+impl VerifyProofFullAndRegisterFactCall {
+    /// Encodes the calldata while recording the source field of each felt.
+    pub fn calldata_source_map(&self) -> CalldataSourceMap {
+        CalldataSourceMap::build(self)
+    }
+}
+
+fn field_path(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_owned()
+    } else {
+        format!("{path}.{field}")
+    }
+}
+
+/// Writes a list without its own length prefix, as used after the synthetic prefixes of the
+/// canonical Cairo types.
+fn encode_elements<T: EncodeAnnotated>(elements: &[T], path: &str, map: &mut CalldataSourceMap) {
+    for (index, element) in elements.iter().enumerate() {
+        element.encode_annotated(&format!("{path}[{index}]"), map);
+    }
+}
+
+impl EncodeAnnotated for Felt {
+    fn encode_annotated(&self, path: &str, map: &mut CalldataSourceMap) {
+        map.write(path.to_owned(), *self);
+    }
+}
+
+impl EncodeAnnotated for u8 {
+    fn encode_annotated(&self, path: &str, map: &mut CalldataSourceMap) {
+        map.write(path.to_owned(), (*self).into());
+    }
+}
+
+impl EncodeAnnotated for u32 {
+    fn encode_annotated(&self, path: &str, map: &mut CalldataSourceMap) {
+        map.write(path.to_owned(), (*self).into());
+    }
+}
+
+impl EncodeAnnotated for u64 {
+    fn encode_annotated(&self, path: &str, map: &mut CalldataSourceMap) {
+        map.write(path.to_owned(), (*self).into());
+    }
+}
+
+impl<T: EncodeAnnotated> EncodeAnnotated for Vec<T> {
+    fn encode_annotated(&self, path: &str, map: &mut CalldataSourceMap) {
+        map.write(field_path(path, "len"), self.len().into());
+        encode_elements(self, path, map);
+    }
+}
+
+/// Implements [`EncodeAnnotated`] for types with derived `Encode`, which writes the fields in
+/// declaration order. The fields must be listed in that same order.
+macro_rules! impl_encode_annotated {
+    ($type:ty { $($field:ident),* $(,)? }) => {
+        impl EncodeAnnotated for $type {
+            fn encode_annotated(&self, path: &str, map: &mut CalldataSourceMap) {
+                $(self.$field.encode_annotated(&field_path(path, stringify!($field)), map);)*
+            }
+        }
+    };
+}
+
+impl_encode_annotated!(VerifyProofInitialCall {
+    job_id,
+    verifier_config,
+    stark_proof,
+});
+impl_encode_annotated!(VerifyProofStepCall {
+    job_id,
+    state_constant,
+    state_variable,
+    witness,
+});
+impl_encode_annotated!(VerifyProofFinalAndRegisterFactCall {
+    job_id,
+    state_constant,
+    state_variable,
+    last_layer_coefficients,
+});
+impl_encode_annotated!(VerifyProofFullAndRegisterFactCall {
+    verifier_config,
+    stark_proof,
+});
+impl_encode_annotated!(VerifierConfiguration {
+    layout,
+    hasher,
+    stone_version,
+    memory_verification,
+});
+impl_encode_annotated!(StarkProofWithSerde {
+    config,
+    public_input,
+    unsent_commitment,
+    witness,
+});
+impl_encode_annotated!(StarkConfigWithSerde {
+    traces,
+    composition,
+    fri,
+    proof_of_work,
+    log_trace_domain_size,
+    n_queries,
+    log_n_cosets,
+    n_verifier_friendly_commitment_layers,
+});
+impl_encode_annotated!(TracesConfigWithSerde {
+    original,
+    interaction,
+});
+impl_encode_annotated!(TableCommitmentConfigWithSerde { n_columns, vector });
+impl_encode_annotated!(VectorCommitmentConfigWithSerde {
+    height,
+    n_verifier_friendly_commitment_layers,
+});
+impl_encode_annotated!(ProofOfWorkConfigWithSerde { n_bits });
+impl_encode_annotated!(SegmentInfo {
+    begin_addr,
+    stop_ptr,
+});
+impl_encode_annotated!(AddrValue { address, value });
+impl_encode_annotated!(ContinuousPageHeader {
+    start_address,
+    size,
+    hash,
+    prod,
+});
+impl_encode_annotated!(StarkUnsentCommitmentWithSerde {
+    traces,
+    composition,
+    oods_values,
+    fri,
+    proof_of_work,
+});
+impl_encode_annotated!(TracesUnsentCommitmentWithSerde {
+    original,
+    interaction,
+});
+impl_encode_annotated!(FriUnsentCommitmentWithSerde {
+    inner_layers,
+    last_layer_coefficients,
+});
+impl_encode_annotated!(ProofOfWorkUnsentCommitmentWithSerde { nonce });
+impl_encode_annotated!(StarkWitnessWithSerde {
+    traces_decommitment,
+    traces_witness,
+    composition_decommitment,
+    composition_witness,
+    fri_witness,
+});
+impl_encode_annotated!(TracesDecommitmentWithSerde {
+    original,
+    interaction,
+});
+impl_encode_annotated!(TracesWitnessWithSerde {
+    original,
+    interaction,
+});
+impl_encode_annotated!(TableCommitmentWitnessWithSerde { vector });
+impl_encode_annotated!(FriWitnessWithSerde { layers });
+impl_encode_annotated!(FriVerificationStateConstant {
+    n_layers,
+    commitment,
+    eval_points,
+    step_sizes,
+    last_layer_coefficients_hash,
+});
+impl_encode_annotated!(FriVerificationStateVariable { iter, queries });
+impl_encode_annotated!(FriLayerWitness {
+    leaves,
+    table_witness,
+});
+impl_encode_annotated!(TableCommitment {
+    config,
+    vector_commitment,
+});
+impl_encode_annotated!(TableCommitmentConfig { n_columns, vector });
+impl_encode_annotated!(VectorCommitment {
+    config,
+    commitment_hash,
+});
+impl_encode_annotated!(VectorCommitmentConfig {
+    height,
+    n_verifier_friendly_commitment_layers,
+});
+impl_encode_annotated!(FriLayerQuery {
+    index,
+    y_value,
+    x_inv_value,
+});
+impl_encode_annotated!(TableCommitmentWitness { vector });
+impl_encode_annotated!(VectorCommitmentWitness { authentications });
+
+// The following types have hand-written `Encode` implementations in `bindings`, which these must
+// be kept in sync with.
+
+impl EncodeAnnotated for FriConfigWithSerde {
+    fn encode_annotated(&self, path: &str, map: &mut CalldataSourceMap) {
+        self.log_input_size
+            .encode_annotated(&field_path(path, "log_input_size"), map);
+        self.n_layers
+            .encode_annotated(&field_path(path, "n_layers"), map);
+
+        // The length prefix counts felts instead of elements
+        let inner_layers = field_path(path, "inner_layers");
+        map.write(
+            field_path(&inner_layers, "len"),
+            (self.inner_layers.len() * 3).into(),
+        );
+        encode_elements(&self.inner_layers, &inner_layers, map);
+
+        self.fri_step_sizes
+            .encode_annotated(&field_path(path, "fri_step_sizes"), map);
+        self.log_last_layer_degree_bound
+            .encode_annotated(&field_path(path, "log_last_layer_degree_bound"), map);
+    }
+}
+
+impl EncodeAnnotated for PublicInputWithSerde {
+    fn encode_annotated(&self, path: &str, map: &mut CalldataSourceMap) {
+        self.log_n_steps
+            .encode_annotated(&field_path(path, "log_n_steps"), map);
+        self.range_check_min
+            .encode_annotated(&field_path(path, "range_check_min"), map);
+        self.range_check_max
+            .encode_annotated(&field_path(path, "range_check_max"), map);
+        self.layout
+            .encode_annotated(&field_path(path, "layout"), map);
+        self.dynamic_params
+            .encode_annotated(&field_path(path, "dynamic_params"), map);
+
+        encode_double_prefixed(
+            &self.segments,
+            2,
+            "n_segments",
+            &field_path(path, "segments"),
+            path,
+            map,
+        );
+
+        self.padding_addr
+            .encode_annotated(&field_path(path, "padding_addr"), map);
+        self.padding_value
+            .encode_annotated(&field_path(path, "padding_value"), map);
+
+        encode_double_prefixed(
+            &self.main_page,
+            2,
+            "main_page_len",
+            &field_path(path, "main_page"),
+            path,
+            map,
+        );
+        encode_double_prefixed(
+            &self.continuous_page_headers,
+            4,
+            "n_continuous_pages",
+            &field_path(path, "continuous_page_headers"),
+            path,
+            map,
+        );
+    }
+}
+
+impl EncodeAnnotated for TableDecommitmentWithSerde {
+    fn encode_annotated(&self, path: &str, map: &mut CalldataSourceMap) {
+        encode_double_prefixed(
+            &self.values,
+            1,
+            "n_values",
+            &field_path(path, "values"),
+            path,
+            map,
+        );
+    }
+}
+
+impl EncodeAnnotated for VectorCommitmentWitnessWithSerde {
+    fn encode_annotated(&self, path: &str, map: &mut CalldataSourceMap) {
+        encode_double_prefixed(
+            &self.authentications,
+            1,
+            "n_authentications",
+            &field_path(path, "authentications"),
+            path,
+            map,
+        );
+    }
+}
+
+/// Writes a list prefixed by both its element count, recorded under the Cairo field name
+/// `count_field`, and its felt length.
+fn encode_double_prefixed<T: EncodeAnnotated>(
+    elements: &[T],
+    felts_per_element: usize,
+    count_field: &str,
+    path: &str,
+    parent_path: &str,
+    map: &mut CalldataSourceMap,
+) {
+    map.write(field_path(parent_path, count_field), elements.len().into());
+    map.write(
+        field_path(path, "len"),
+        (elements.len() * felts_per_element).into(),
+    );
+    encode_elements(elements, path, map);
+}
+
+#[cfg(all(
+    test,
+    feature = "recursive",
+    feature = "keccak_160_lsb",
+    feature = "stone5"
+))]
+mod tests {
+    use crate::{fixtures, Felt, VerifyProofFullAndRegisterFactCall};
+
+    #[test]
+    fn source_map_matches_encoding() {
+        let calls = fixtures::calls(Felt::ONE);
+
+        let initial = calls.initial.calldata_source_map();
+        assert_eq!(initial.calldata(), calls.initial.calldata());
+        assert_eq!(initial.path_at(0), Some("job_id"));
+
+        for step in &calls.intermediate_steps {
+            assert_eq!(step.calldata_source_map().calldata(), step.calldata());
+        }
+
+        assert_eq!(
+            calls.final_step.calldata_source_map().calldata(),
+            calls.final_step.calldata()
+        );
+
+        let full = VerifyProofFullAndRegisterFactCall {
+            verifier_config: fixtures::verifier_config(),
+            stark_proof: fixtures::proof().into(),
+        };
+        let full_map = full.calldata_source_map();
+        assert_eq!(full_map.calldata(), full.calldata());
+        assert!(full_map
+            .entries()
+            .iter()
+            .all(|entry| !entry.path.is_empty()));
+    }
+}
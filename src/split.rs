@@ -1,7 +1,8 @@
-use starknet_core::types::Felt;
+use std::iter::FusedIterator;
+
+use starknet_core::types::{Felt, NonZeroFelt};
 use starknet_crypto::poseidon_hash_many;
 use swiftness::{
-    commit::stark_commit,
     oods::{eval_oods_boundary_poly_at_points, OodsEvaluationInfo},
     queries::{generate_queries, queries_to_points},
     types::{StarkCommitment, StarkWitness},
@@ -11,12 +12,14 @@ use swiftness_air::{
     layout::{GenericLayoutTrait, LayoutTrait},
     public_memory::PublicInput,
 };
+use swiftness_commitment::{
+    table::types::Witness as SwiftnessTableCommitmentWitness,
+    vector::types::Witness as SwiftnessVectorCommitmentWitness,
+};
 use swiftness_fri::{
     first_layer::gather_first_layer_queries,
     group::get_fri_group,
-    layer::{
-        compute_next_layer, FriLayerComputationParams, FriLayerQuery as SwiftnessFriLayerQuery,
-    },
+    layer::{compute_next_layer, FriLayerComputationParams},
     types::LayerWitness,
 };
 use swiftness_stark::types::StarkProof;
@@ -27,30 +30,99 @@ use crate::{
         FriLayerQuery, FriLayerWitness, FriVerificationStateConstant, FriVerificationStateVariable,
         StarkProofWithSerde, TableCommitment, TableCommitmentConfig, TableCommitmentWitness,
         VectorCommitment, VectorCommitmentConfig, VectorCommitmentWitness, VerifierConfiguration,
-        VerifyProofFinalAndRegisterFactCall, VerifyProofInitialCall, VerifyProofStepCall,
+        VerifyProofFinalAndRegisterFactCall, VerifyProofFullAndRegisterFactCall,
+        VerifyProofInitialCall, VerifyProofStepCall,
+    },
+    commit::{public_input_hash, stark_commit},
+    config::{ConfigError, Layout},
+    crypto::table_decommit,
+    error::Error,
+    fact::{compute_fact, security_bits},
+    fri::{
+        expected_sibling_count, to_binding_query, to_swiftness_query, MAX_FRI_LAYERS,
+        MAX_FRI_STEP_SIZE, MAX_LAST_LAYER_LOG_DEGREE_BOUND, MIN_FRI_LAYERS, MIN_FRI_STEP_SIZE,
     },
-    IntegrityCalls,
+    pack::single_call_transaction_calldata_len,
+    IntegrityCalls, RemainingCalls, VerificationCalls,
 };
 
+/// Largest log2 of the evaluation domain size supported by the verifier.
+const MAX_LOG_EVAL_DOMAIN_SIZE: u64 = 64;
+
 /// A split START proof that can be used to generate Starknet function calls to the `integrity`
 /// verifier contract.
 #[derive(Debug)]
 pub struct SplitProof {
     /// STARK proof with `fri_witness` stripped out.
     pub proof: StarkProofWithSerde,
+    /// The verifier configuration the proof was split with.
+    pub verifier_config: VerifierConfiguration,
     /// The state constants used throughout all verification steps.
     pub state_const: FriVerificationStateConstant,
     /// An iterator that returns intermediate and final steps.
     pub step_iter: VerifyProofStepParamIter,
 }
 
+/// A [`SplitProof`] with all verification steps generated upfront, which unlike [`SplitProof`] can
+/// be stored and moved around freely.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MaterializedSplitProof {
+    /// STARK proof with `fri_witness` stripped out.
+    pub proof: StarkProofWithSerde,
+    /// The verifier configuration the proof was split with.
+    pub verifier_config: VerifierConfiguration,
+    /// The state constants used throughout all verification steps.
+    pub state_const: FriVerificationStateConstant,
+    /// Intermediate verification steps.
+    pub steps: Vec<SplitProofStep>,
+    /// The state variable of the final step.
+    pub final_state_variable: FriVerificationStateVariable,
+    /// The last layer coefficients supplied in the final step.
+    pub last_layer_coefficients: Vec<Felt>,
+}
+
+/// Data for an intermediate verification step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SplitProofStep {
+    /// The FRI state before the step.
+    pub state_variable: FriVerificationStateVariable,
+    /// The FRI layer witness verified by the step.
+    pub witness: FriLayerWitness,
+}
+
+/// Data for the final verification step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SplitProofFinalStep {
+    /// The FRI state before the step.
+    pub state_variable: FriVerificationStateVariable,
+    /// The last layer coefficients supplied in the step.
+    pub last_layer_coefficients: Vec<Felt>,
+}
+
+/// A verification step produced by [`VerifyProofStepParamIter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyProofStepParam {
+    /// An intermediate step verifying one FRI layer.
+    Intermediate(SplitProofStep),
+    /// The final step, always the last item produced.
+    Final(SplitProofFinalStep),
+}
+
 /// An iterator that produces data necessary for constructing the intermediate and final
-/// verification steps.
-#[derive(Debug)]
+/// verification steps, in order.
+///
+/// The iterator yields each intermediate step followed by exactly one final step, and stops after
+/// the final step or the first error.
+#[derive(Debug, Clone)]
 pub struct VerifyProofStepParamIter {
     next_index: usize,
     n_layers: usize,
-    next_queries: Vec<SwiftnessFriLayerQuery>,
+    finished: bool,
+    failed: bool,
+    next_queries: Vec<FriLayerQuery>,
     fri_group: Vec<Felt>,
     layer_witness: Vec<LayerWitness>,
     eval_points: Vec<Felt>,
@@ -60,139 +132,536 @@ pub struct VerifyProofStepParamIter {
 
 impl SplitProof {
     /// Transforms the split proofs into `integrity` contract binding types by supplying a unique
-    /// job ID and verifier configuration.
-    pub fn into_calls(
+    /// job ID.
+    pub fn into_calls(self, job_id: Felt) -> Result<IntegrityCalls, Error> {
+        Ok(self.materialize()?.into_calls(job_id))
+    }
+
+    /// Generates all verification steps, so that the split proof can be stored or sent elsewhere.
+    pub fn materialize(mut self) -> Result<MaterializedSplitProof, Error> {
+        let (steps, final_step) = self.step_iter.collect_remaining()?;
+
+        Ok(MaterializedSplitProof {
+            proof: self.proof,
+            verifier_config: self.verifier_config,
+            state_const: self.state_const,
+            steps,
+            final_state_variable: final_step.state_variable,
+            last_layer_coefficients: final_step.last_layer_coefficients,
+        })
+    }
+
+    /// Generates only the calls from FRI layer `layer` onward, for resuming a job whose earlier
+    /// intermediate steps have already been submitted.
+    ///
+    /// The layers before `layer` are still computed to derive the FRI state, so the calls are
+    /// identical to the corresponding ones of an uninterrupted run.
+    pub fn resume_from_layer(
         mut self,
+        layer: usize,
         job_id: Felt,
-        verifier_config: VerifierConfiguration,
-    ) -> IntegrityCalls {
+    ) -> Result<RemainingCalls, Error> {
+        self.step_iter.skip_to_layer(layer)?;
+        self.into_remaining_calls(job_id)
+    }
+
+    /// Generates only the calls from the FRI state recorded after the last submitted step, for
+    /// resuming a job whose earlier intermediate steps have already been submitted.
+    ///
+    /// Unlike [`SplitProof::resume_from_layer`], the layers before the state's are not computed, so
+    /// `state` must be the one the job actually reached.
+    pub fn resume_from_state(
+        mut self,
+        state: &FriVerificationStateVariable,
+        job_id: Felt,
+    ) -> Result<RemainingCalls, Error> {
+        self.step_iter.resume_from(state)?;
+        self.into_remaining_calls(job_id)
+    }
+
+    fn into_remaining_calls(mut self, job_id: Felt) -> Result<RemainingCalls, Error> {
+        let (steps, final_step) = self.step_iter.collect_remaining()?;
+
+        Ok(RemainingCalls {
+            intermediate_steps: steps
+                .into_iter()
+                .map(|step| VerifyProofStepCall {
+                    job_id,
+                    state_constant: self.state_const.clone(),
+                    state_variable: step.state_variable,
+                    witness: step.witness,
+                })
+                .collect(),
+            final_step: VerifyProofFinalAndRegisterFactCall {
+                job_id,
+                state_constant: self.state_const,
+                state_variable: final_step.state_variable,
+                last_layer_coefficients: final_step.last_layer_coefficients,
+            },
+        })
+    }
+}
+
+impl MaterializedSplitProof {
+    /// Transforms the split proofs into `integrity` contract binding types by supplying a unique
+    /// job ID.
+    pub fn into_calls(self, job_id: Felt) -> IntegrityCalls {
         IntegrityCalls {
             initial: VerifyProofInitialCall {
                 job_id,
-                verifier_config,
+                verifier_config: self.verifier_config,
                 stark_proof: self.proof,
             },
             intermediate_steps: self
-                .step_iter
-                .by_ref()
-                .map(|(state_var, witness)| VerifyProofStepCall {
+                .steps
+                .into_iter()
+                .map(|step| VerifyProofStepCall {
                     job_id,
                     state_constant: self.state_const.clone(),
-                    state_variable: state_var,
-                    witness,
+                    state_variable: step.state_variable,
+                    witness: step.witness,
                 })
                 .collect(),
-            final_step: {
-                let (state_var, witness) = self.step_iter.final_step();
-                VerifyProofFinalAndRegisterFactCall {
-                    job_id,
-                    state_constant: self.state_const,
-                    state_variable: state_var,
-                    last_layer_coefficients: witness,
-                }
+            final_step: VerifyProofFinalAndRegisterFactCall {
+                job_id,
+                state_constant: self.state_const,
+                state_variable: self.final_state_variable,
+                last_layer_coefficients: self.last_layer_coefficients,
             },
         }
     }
 }
 
 impl VerifyProofStepParamIter {
-    /// Generates the final step after the iterator has been exhausted.
+    /// Index of the FRI layer verified by the next intermediate step.
+    pub fn next_layer(&self) -> usize {
+        self.next_index
+    }
+
+    /// Number of FRI layers verified in intermediate steps.
+    pub fn n_layers(&self) -> usize {
+        self.n_layers
+    }
+
+    /// Advances the iterator so that the next step verifies FRI layer `layer`, discarding the steps
+    /// in between.
+    pub fn skip_to_layer(&mut self, layer: usize) -> Result<(), Error> {
+        self.check_resume_layer(layer)?;
+
+        while self.next_index < layer {
+            if let Some(Err(err)) = self.next() {
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Advances the iterator to the layer of a recorded `state`, continuing from its queries
+    /// without recomputing the layers in between.
     ///
-    /// The function panics if it's call before the iterator is exhausted. Make sure `.next()`
-    /// returns [`None`] before calling.
-    pub fn final_step(self) -> (FriVerificationStateVariable, Vec<Felt>) {
-        if self.next_index != self.n_layers {
-            panic!("`final_step` can only be used when the iterator has been exhausted")
+    /// Only the query indices of the state are checked against the proof, as checking the values
+    /// would require recomputing those layers.
+    pub fn resume_from(&mut self, state: &FriVerificationStateVariable) -> Result<(), Error> {
+        let layer = state.iter as usize;
+        self.check_resume_layer(layer)?;
+
+        let expected_indices = self.query_indices_at(layer)?;
+        let query = expected_indices
+            .iter()
+            .zip(&state.queries)
+            .position(|(expected, query)| *expected != query.index)
+            .unwrap_or(expected_indices.len().min(state.queries.len()));
+        if query != expected_indices.len() || query != state.queries.len() {
+            return Err(Error::ResumeStateMismatch { layer, query });
         }
 
-        (self.state_variable(), self.last_layer_coefficients)
+        self.next_index = layer;
+        self.next_queries = state.queries.clone();
+
+        Ok(())
     }
 
-    fn state_variable(&self) -> FriVerificationStateVariable {
-        FriVerificationStateVariable {
-            iter: self.next_index.try_into().unwrap(),
-            queries: self
-                .next_queries
-                .iter()
-                .map(|query| FriLayerQuery {
-                    index: query.index,
-                    y_value: query.y_value,
-                    x_inv_value: query.x_inv_value,
-                })
-                .collect(),
+    /// Computes the indices of the queries of FRI layer `layer` by folding the current queries, in
+    /// ascending order as `compute_next_layer` produces them.
+    fn query_indices_at(&self, layer: usize) -> Result<Vec<Felt>, Error> {
+        let mut indices = self
+            .next_queries
+            .iter()
+            .map(|query| query.index)
+            .collect::<Vec<_>>();
+        for step_size in &self.step_sizes[self.next_index..layer] {
+            let step_size = u64::try_from(*step_size).map_err(|_| Error::Overflow {
+                field: "fri_step_sizes",
+                value: *step_size,
+            })?;
+            let coset_size = NonZeroFelt::from_felt_unchecked(Felt::from(1u64 << step_size));
+            for index in &mut indices {
+                *index = index.floor_div(&coset_size);
+            }
+            indices.dedup();
+        }
+
+        Ok(indices)
+    }
+
+    fn check_resume_layer(&self, layer: usize) -> Result<(), Error> {
+        if self.failed {
+            return Err(Error::PreviousStepFailed);
+        }
+        if !(self.next_index..=self.n_layers).contains(&layer) {
+            return Err(Error::ResumeLayerOutOfRange {
+                layer,
+                next_layer: self.next_index,
+                n_layers: self.n_layers,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Generates all remaining steps, split into the intermediate steps and the final step.
+    fn collect_remaining(&mut self) -> Result<(Vec<SplitProofStep>, SplitProofFinalStep), Error> {
+        if self.failed {
+            return Err(Error::PreviousStepFailed);
+        }
+
+        let mut steps = Vec::with_capacity(self.len().saturating_sub(1));
+        for step in self.by_ref() {
+            match step? {
+                VerifyProofStepParam::Intermediate(step) => steps.push(step),
+                VerifyProofStepParam::Final(final_step) => return Ok((steps, final_step)),
+            }
+        }
+
+        Err(Error::StepsExhausted)
+    }
+
+    fn state_variable(&self) -> Result<FriVerificationStateVariable, Error> {
+        Ok(FriVerificationStateVariable {
+            iter: u32::try_from(self.next_index).map_err(|_| Error::Overflow {
+                field: "iter",
+                value: self.next_index.into(),
+            })?,
+            queries: self.next_queries.clone(),
+        })
+    }
+
+    fn final_step(&mut self) -> Result<SplitProofFinalStep, Error> {
+        let state_variable = self.state_variable()?;
+        self.finished = true;
+
+        Ok(SplitProofFinalStep {
+            state_variable,
+            last_layer_coefficients: std::mem::take(&mut self.last_layer_coefficients),
+        })
+    }
+
+    fn next_step(&mut self) -> Result<SplitProofStep, Error> {
+        let layer = self.next_index;
+        let (Some(target_layer_witness), Some(step_size), Some(eval_point)) = (
+            self.layer_witness.get(layer),
+            self.step_sizes.get(layer),
+            self.eval_points.get(layer),
+        ) else {
+            return Err(Error::LengthMismatch {
+                field: "fri_witness.layers",
+                expected: self.n_layers,
+                actual: layer,
+            });
+        };
+
+        // Step sizes have been validated when splitting the proof.
+        let coset_size = 1u64
+            << u64::try_from(*step_size).map_err(|_| Error::Overflow {
+                field: "fri_step_sizes",
+                value: *step_size,
+            })?;
+
+        // `compute_next_layer` panics on missing siblings, so the leaf count is checked upfront.
+        let expected_leaves = expected_sibling_count(
+            self.next_queries.iter().map(|query| query.index),
+            coset_size,
+        );
+        if expected_leaves != target_layer_witness.leaves.len() {
+            return Err(Error::LeafCountMismatch {
+                layer,
+                expected: expected_leaves,
+                actual: target_layer_witness.leaves.len(),
+            });
         }
+
+        let params = FriLayerComputationParams {
+            coset_size: coset_size.into(),
+            fri_group: self.fri_group.clone(),
+            eval_point: *eval_point,
+        };
+
+        let state_var = self.state_variable()?;
+        let witness = FriLayerWitness {
+            leaves: target_layer_witness.leaves.clone(),
+            table_witness: TableCommitmentWitness {
+                vector: VectorCommitmentWitness {
+                    authentications: target_layer_witness
+                        .table_witness
+                        .vector
+                        .authentications
+                        .clone(),
+                },
+            },
+        };
+
+        let mut queries = self
+            .next_queries
+            .iter()
+            .map(to_swiftness_query)
+            .collect::<Vec<_>>();
+        let (next_queries, _, _) = compute_next_layer(
+            &mut queries,
+            &mut target_layer_witness.leaves.to_owned(),
+            params,
+        )
+        .map_err(|error| Error::LayerComputation { layer, error })?;
+
+        self.next_index += 1;
+        self.next_queries = next_queries.into_iter().map(to_binding_query).collect();
+
+        Ok(SplitProofStep {
+            state_variable: state_var,
+            witness,
+        })
     }
 }
 
 impl Iterator for VerifyProofStepParamIter {
-    type Item = (FriVerificationStateVariable, FriLayerWitness);
+    type Item = Result<VerifyProofStepParam, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.next_index == self.n_layers {
-            // Intermediate layers exhausted. Call `.final_step()` to retrieve the final step
-            // instead.
-            None
+        if self.failed || self.finished {
+            return None;
+        }
+
+        let step = if self.next_index == self.n_layers {
+            self.final_step().map(VerifyProofStepParam::Final)
         } else {
-            let target_layer_witness = self.layer_witness.get(self.next_index).unwrap();
-
-            // Params.
-            let coset_size = Felt::TWO.pow_felt(self.step_sizes.get(self.next_index).unwrap());
-            let params = FriLayerComputationParams {
-                coset_size,
-                fri_group: self.fri_group.clone(),
-                eval_point: *self.eval_points.get(self.next_index).unwrap(),
-            };
-
-            let state_var = self.state_variable();
-            let witness = FriLayerWitness {
-                leaves: target_layer_witness.leaves.clone(),
-                table_witness: TableCommitmentWitness {
-                    vector: VectorCommitmentWitness {
-                        authentications: target_layer_witness
-                            .table_witness
-                            .vector
-                            .authentications
-                            .clone(),
-                    },
-                },
-            };
+            self.next_step().map(VerifyProofStepParam::Intermediate)
+        };
+        self.failed = step.is_err();
+        Some(step)
+    }
 
-            let (next_queries, _, _) = compute_next_layer(
-                &mut self.next_queries,
-                &mut target_layer_witness.leaves.to_owned(),
-                params,
-            )
-            .unwrap();
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = if self.failed || self.finished {
+            0
+        } else {
+            // All remaining intermediate layers plus the final step.
+            self.n_layers - self.next_index + 1
+        };
+        (remaining, Some(remaining))
+    }
+}
 
-            self.next_index += 1;
-            self.next_queries = next_queries;
+impl ExactSizeIterator for VerifyProofStepParamIter {}
 
-            Some((state_var, witness))
+impl FusedIterator for VerifyProofStepParamIter {}
+
+/// Dispatches [`split_proof_impl`] to the layout type matching a [`Layout`] variant. Each variant is
+/// listed along with the cargo feature gating it.
+macro_rules! split_proof_with_layout {
+    (
+        $layout:expr, $proof:expr, $verifier_config:expr, $verify:expr,
+        $($variant:ident => $module:ident, $name:literal);+ $(;)?
+    ) => {
+        match $layout {
+            $(
+                Layout::$variant => {
+                    #[cfg(feature = $name)]
+                    {
+                        split_proof_impl::<swiftness_air::layout::$module::Layout>(
+                            $proof,
+                            $verifier_config,
+                            $verify,
+                        )
+                    }
+                    #[cfg(not(feature = $name))]
+                    {
+                        Err(ConfigError::NotEnabled {
+                            kind: "layout",
+                            name: $name,
+                        }
+                        .into())
+                    }
+                }
+            )+
         }
+    };
+}
+
+/// Splits a [`StarkProof`] into a multi-step verification process, selecting the layout at runtime
+/// from the proof's `public_input.layout`.
+///
+/// The layout must be enabled as a cargo feature. Like [`split_proof`], this function does *not*
+/// verify the proof.
+pub fn split_proof_dyn(
+    proof: StarkProof,
+    verifier_config: VerifierConfiguration,
+) -> Result<SplitProof, Error> {
+    split_proof_dyn_impl(proof, verifier_config, false)
+}
+
+/// Like [`split_proof_dyn`], but also fully verifies the proof like [`split_and_verify`].
+pub fn split_and_verify_dyn(
+    proof: StarkProof,
+    verifier_config: VerifierConfiguration,
+) -> Result<SplitProof, Error> {
+    split_proof_dyn_impl(proof, verifier_config, true)
+}
+
+fn split_proof_dyn_impl(
+    proof: StarkProof,
+    verifier_config: VerifierConfiguration,
+    verify: bool,
+) -> Result<SplitProof, Error> {
+    split_proof_with_layout!(
+        Layout::from_felt(proof.public_input.layout)?,
+        proof,
+        verifier_config,
+        verify,
+        Dex => dex, "dex";
+        Dynamic => dynamic, "dynamic";
+        Recursive => recursive, "recursive";
+        RecursiveWithPoseidon => recursive_with_poseidon, "recursive_with_poseidon";
+        Small => small, "small";
+        Starknet => starknet, "starknet";
+        StarknetWithKeccak => starknet_with_keccak, "starknet_with_keccak";
+    )
+}
+
+/// Derives the FRI state a job starts from out of the proof sent with its initial call, whose FRI
+/// layer witnesses are stripped out, like `verify_proof_initial` does.
+pub(crate) fn initial_fri_state(
+    mut proof: StarkProof,
+    verifier_config: VerifierConfiguration,
+) -> Result<(FriVerificationStateConstant, FriVerificationStateVariable), Error> {
+    // The layer witnesses are only used by the steps, so empty ones pass the shape checks.
+    let n_layers = fri_inner_layer_count(proof.config.fri.n_layers)?;
+    proof.witness.fri_witness.layers.resize(
+        n_layers,
+        LayerWitness {
+            leaves: vec![],
+            table_witness: SwiftnessTableCommitmentWitness {
+                vector: SwiftnessVectorCommitmentWitness {
+                    authentications: vec![],
+                },
+            },
+        },
+    );
+
+    let split_proof = split_proof_dyn(proof, verifier_config)?;
+    let state_variable = split_proof.step_iter.state_variable()?;
+    Ok((split_proof.state_const, state_variable))
+}
+
+/// Builds the calls for verifying a [`StarkProof`], using a single
+/// `verify_proof_full_and_register_fact` call when its transaction calldata takes at most
+/// `max_calldata_len` felts, and splitting the proof with [`split_proof_dyn`] otherwise.
+///
+/// `job_id` is only used when the proof is split.
+pub fn verification_calls(
+    proof: StarkProof,
+    verifier_config: VerifierConfiguration,
+    job_id: Felt,
+    max_calldata_len: usize,
+) -> Result<VerificationCalls, Error> {
+    verifier_config.validate(&proof)?;
+
+    let full_call = VerifyProofFullAndRegisterFactCall {
+        verifier_config,
+        stark_proof: proof.clone().into(),
+    };
+    // The contract address doesn't affect the calldata length.
+    if single_call_transaction_calldata_len(&full_call.call(Felt::ZERO)) <= max_calldata_len {
+        return Ok(VerificationCalls::Full(Box::new(full_call)));
     }
+
+    Ok(VerificationCalls::Split(Box::new(
+        split_proof_dyn(proof, full_call.verifier_config)?.into_calls(job_id)?,
+    )))
 }
 
 /// Splits a [`StarkProof`] into a multi-step verification process.
 ///
-/// This function does *not* verify the proof.
+/// The hasher and Stone version are taken from `verifier_config` and must be enabled as cargo
+/// features. The configuration is rejected if it disagrees with the proof. This function does
+/// *not* verify the proof: use [`split_and_verify`] for that.
 pub fn split_proof<Layout: GenericLayoutTrait + LayoutTrait>(
     proof: StarkProof,
-) -> Result<SplitProof, swiftness_stark::stark::Error> {
+    verifier_config: VerifierConfiguration,
+) -> Result<SplitProof, Error> {
+    split_proof_impl::<Layout>(proof, verifier_config, false)
+}
+
+/// Splits a [`StarkProof`] like [`split_proof`], while running the rest of the verification
+/// performed by the `integrity` verifier contract off-chain.
+///
+/// On top of the commitment phase, which includes the OODS consistency check and proof of work
+/// verification, the STARK config and public input are validated, the trace and composition
+/// decommitments are checked against their commitments, every FRI layer is verified, and the fact
+/// is computed with the configured memory verification mode. The split proof is only returned if
+/// all checks pass.
+pub fn split_and_verify<Layout: GenericLayoutTrait + LayoutTrait>(
+    proof: StarkProof,
+    verifier_config: VerifierConfiguration,
+) -> Result<SplitProof, Error> {
+    split_proof_impl::<Layout>(proof, verifier_config, true)
+}
+
+fn split_proof_impl<Layout: GenericLayoutTrait + LayoutTrait>(
+    proof: StarkProof,
+    verifier_config: VerifierConfiguration,
+    verify: bool,
+) -> Result<SplitProof, Error> {
+    verifier_config.validate(&proof)?;
+    let hasher = verifier_config.hasher()?.ensure_enabled()?;
+    let stone_version = verifier_config.stone_version()?.ensure_enabled()?;
+
     let n_original_columns = Layout::get_num_columns_first(&proof.public_input)
-        .ok_or(swiftness_stark::stark::Error::ColumnMissing)?;
-    let n_interaction_columns = Layout::get_num_columns_second(&proof.public_input)
-        .ok_or(swiftness_stark::stark::Error::ColumnMissing)?;
+        .ok_or(Error::ColumnMissing { trace: "original" })?;
+    let n_interaction_columns =
+        Layout::get_num_columns_second(&proof.public_input).ok_or(Error::ColumnMissing {
+            trace: "interaction",
+        })?;
+
+    // `swiftness` panics on malformed proofs, so their shape is checked upfront.
+    validate_proof_shape::<Layout>(&proof, n_original_columns, n_interaction_columns)?;
+
+    if verify {
+        proof
+            .config
+            .validate(
+                security_bits(&proof.config),
+                n_original_columns.into(),
+                n_interaction_columns.into(),
+            )
+            .map_err(Error::InvalidConfig)?;
+    }
 
     // Validate the public input.
     let stark_domains = StarkDomains::new(
         proof.config.log_trace_domain_size,
         proof.config.log_n_cosets,
     );
+    if verify {
+        Layout::validate_public_input(&proof.public_input, &stark_domains)
+            .map_err(Error::InvalidPublicInput)?;
+    }
 
     // Compute the initial hash seed for the Fiat-Shamir transcript.
-    let digest = proof
-        .public_input
-        .get_hash(proof.config.n_verifier_friendly_commitment_layers);
+    let digest = public_input_hash(
+        &proof.public_input,
+        proof.config.n_verifier_friendly_commitment_layers,
+        stone_version,
+    );
     // Construct the transcript.
     let mut transcript = Transcript::new(digest);
 
@@ -203,9 +672,10 @@ pub fn split_proof<Layout: GenericLayoutTrait + LayoutTrait>(
         &proof.unsent_commitment,
         &proof.config,
         &stark_domains,
+        hasher,
     )?;
 
-    let state_const = commitment_to_const_state(&stark_commitment.fri);
+    let state_const = commitment_to_const_state(&stark_commitment.fri)?;
 
     // Generate queries.
     let queries = generate_queries(
@@ -214,6 +684,41 @@ pub fn split_proof<Layout: GenericLayoutTrait + LayoutTrait>(
         stark_domains.eval_domain_size,
     );
 
+    // Decommitments of the first layer, which are otherwise checked by `verify_proof_initial`.
+    if verify {
+        let witness = &proof.witness;
+        let decommitments = [
+            (
+                "original trace",
+                &stark_commitment.traces.original,
+                &witness.traces_decommitment.original,
+                &witness.traces_witness.original,
+            ),
+            (
+                "interaction trace",
+                &stark_commitment.traces.interaction,
+                &witness.traces_decommitment.interaction,
+                &witness.traces_witness.interaction,
+            ),
+            (
+                "composition",
+                &stark_commitment.composition,
+                &witness.composition_decommitment,
+                &witness.composition_witness,
+            ),
+        ];
+        for (table, commitment, decommitment, witness) in decommitments {
+            table_decommit(
+                hasher,
+                commitment,
+                &queries,
+                decommitment,
+                &witness.vector.authentications,
+            )
+            .map_err(|error| Error::Decommitment { table, error })?;
+        }
+    }
+
     // STARK verify phase.
     let step_iter = generate_step_iter::<Layout>(
         n_original_columns,
@@ -223,16 +728,31 @@ pub fn split_proof<Layout: GenericLayoutTrait + LayoutTrait>(
         stark_commitment,
         &proof.witness,
         &stark_domains,
-    );
+    )?;
+
+    if verify {
+        compute_fact(&proof.public_input, verifier_config.memory_verification()?)?;
+    }
 
+    // FRI layer witnesses are sent with the step calls instead of the initial call.
     let mut proof: StarkProofWithSerde = proof.into();
     proof.witness.fri_witness.layers.clear();
 
-    Ok(SplitProof {
+    let split_proof = SplitProof {
         proof,
+        verifier_config,
         state_const,
         step_iter,
-    })
+    };
+
+    // FRI layers are verified the same way as the intermediate and final steps would be.
+    if verify {
+        split_proof
+            .preflight()
+            .map_err(|err| Error::Preflight(Box::new(err)))?;
+    }
+
+    Ok(split_proof)
 }
 
 fn generate_step_iter<Layout: LayoutTrait>(
@@ -243,7 +763,7 @@ fn generate_step_iter<Layout: LayoutTrait>(
     commitment: StarkCommitment<Layout::InteractionElements>,
     witness: &StarkWitness,
     stark_domains: &StarkDomains,
-) -> VerifyProofStepParamIter {
+) -> Result<VerifyProofStepParamIter, Error> {
     // Compute query points.
     let points = queries_to_points(queries, stark_domains);
 
@@ -264,22 +784,28 @@ fn generate_step_iter<Layout: LayoutTrait>(
         &witness.composition_decommitment,
     );
 
-    VerifyProofStepParamIter {
+    Ok(VerifyProofStepParamIter {
         next_index: 0,
-        n_layers: (commitment.fri.config.n_layers - 1).try_into().unwrap(),
-        next_queries: gather_first_layer_queries(queries, oods_poly_evals, points),
+        n_layers: fri_inner_layer_count(commitment.fri.config.n_layers)?,
+        finished: false,
+        failed: false,
+        next_queries: gather_first_layer_queries(queries, oods_poly_evals, points)
+            .into_iter()
+            .map(to_binding_query)
+            .collect(),
         fri_group: get_fri_group(),
         layer_witness: witness.fri_witness.layers.to_owned(),
         eval_points: commitment.fri.eval_points,
         step_sizes: commitment.fri.config.fri_step_sizes[1..].to_vec(),
         last_layer_coefficients: commitment.fri.last_layer_coefficients,
-    }
+    })
 }
+
 fn commitment_to_const_state(
     commitment: &swiftness_fri::types::Commitment,
-) -> FriVerificationStateConstant {
-    FriVerificationStateConstant {
-        n_layers: (commitment.config.n_layers - Felt::ONE).try_into().unwrap(),
+) -> Result<FriVerificationStateConstant, Error> {
+    Ok(FriVerificationStateConstant {
+        n_layers: fri_inner_layer_count(commitment.config.n_layers)? as u32,
         commitment: commitment
             .inner_layers
             .iter()
@@ -315,5 +841,283 @@ fn commitment_to_const_state(
             .cloned()
             .collect(),
         last_layer_coefficients_hash: poseidon_hash_many(&commitment.last_layer_coefficients),
+    })
+}
+
+/// Gets the number of FRI layers verified in intermediate steps, i.e. all layers but the last.
+fn fri_inner_layer_count(n_layers: Felt) -> Result<usize, Error> {
+    u64::try_from(n_layers)
+        .ok()
+        .filter(|n_layers| (MIN_FRI_LAYERS..=MAX_FRI_LAYERS).contains(n_layers))
+        .map(|n_layers| n_layers as usize - 1)
+        .ok_or(Error::FriLayerCountOutOfRange {
+            n_layers,
+            min: MIN_FRI_LAYERS,
+            max: MAX_FRI_LAYERS,
+        })
+}
+
+/// Checks the sizes of all proof fields that `swiftness` indexes into without bound checks.
+fn validate_proof_shape<Layout: LayoutTrait>(
+    proof: &StarkProof,
+    n_original_columns: u32,
+    n_interaction_columns: u32,
+) -> Result<(), Error> {
+    let config = &proof.config;
+    let fri_config = &config.fri;
+
+    let n_inner_layers = fri_inner_layer_count(fri_config.n_layers)?;
+    check_len(
+        "config.fri.fri_step_sizes",
+        n_inner_layers + 1,
+        fri_config.fri_step_sizes.len(),
+    )?;
+    check_len(
+        "config.fri.inner_layers",
+        n_inner_layers,
+        fri_config.inner_layers.len(),
+    )?;
+    check_len(
+        "unsent_commitment.fri.inner_layers",
+        n_inner_layers,
+        proof.unsent_commitment.fri.inner_layers.len(),
+    )?;
+    check_len(
+        "witness.fri_witness.layers",
+        n_inner_layers,
+        proof.witness.fri_witness.layers.len(),
+    )?;
+
+    if fri_config.fri_step_sizes[0] != Felt::ZERO {
+        return Err(Error::FirstFriStepNotZero(fri_config.fri_step_sizes[0]));
+    }
+    for (layer, step_size) in fri_config.fri_step_sizes.iter().enumerate().skip(1) {
+        if !u64::try_from(*step_size)
+            .is_ok_and(|step_size| (MIN_FRI_STEP_SIZE..=MAX_FRI_STEP_SIZE).contains(&step_size))
+        {
+            return Err(Error::FriStepSizeOutOfRange {
+                layer,
+                step_size: *step_size,
+                min: MIN_FRI_STEP_SIZE,
+                max: MAX_FRI_STEP_SIZE,
+            });
+        }
+    }
+
+    let log_last_layer_degree_bound = check_max(
+        "config.fri.log_last_layer_degree_bound",
+        fri_config.log_last_layer_degree_bound,
+        MAX_LAST_LAYER_LOG_DEGREE_BOUND,
+    )?;
+    check_len(
+        "unsent_commitment.fri.last_layer_coefficients",
+        1 << log_last_layer_degree_bound,
+        proof.unsent_commitment.fri.last_layer_coefficients.len(),
+    )?;
+
+    let log_trace_domain_size = check_max(
+        "config.log_trace_domain_size",
+        config.log_trace_domain_size,
+        MAX_LOG_EVAL_DOMAIN_SIZE,
+    )?;
+    let log_n_cosets = check_max(
+        "config.log_n_cosets",
+        config.log_n_cosets,
+        MAX_LOG_EVAL_DOMAIN_SIZE,
+    )?;
+    check_max(
+        "config.log_trace_domain_size + config.log_n_cosets",
+        (log_trace_domain_size + log_n_cosets).into(),
+        MAX_LOG_EVAL_DOMAIN_SIZE,
+    )?;
+
+    check_len(
+        "unsent_commitment.oods_values",
+        Layout::MASK_SIZE + Layout::CONSTRAINT_DEGREE,
+        proof.unsent_commitment.oods_values.len(),
+    )?;
+
+    let n_queries = usize::try_from(config.n_queries).map_err(|_| Error::Overflow {
+        field: "config.n_queries",
+        value: config.n_queries,
+    })?;
+    let decommitment_len = |n_columns: usize| {
+        n_queries.checked_mul(n_columns).ok_or(Error::Overflow {
+            field: "config.n_queries",
+            value: config.n_queries,
+        })
+    };
+    check_len(
+        "witness.traces_decommitment.original.values",
+        decommitment_len(n_original_columns as usize)?,
+        proof.witness.traces_decommitment.original.values.len(),
+    )?;
+    check_len(
+        "witness.traces_decommitment.interaction.values",
+        decommitment_len(n_interaction_columns as usize)?,
+        proof.witness.traces_decommitment.interaction.values.len(),
+    )?;
+    check_len(
+        "witness.composition_decommitment.values",
+        decommitment_len(Layout::CONSTRAINT_DEGREE)?,
+        proof.witness.composition_decommitment.values.len(),
+    )?;
+
+    Ok(())
+}
+
+fn check_len(field: &'static str, expected: usize, actual: usize) -> Result<(), Error> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(Error::LengthMismatch {
+            field,
+            expected,
+            actual,
+        })
+    }
+}
+
+fn check_max(field: &'static str, value: Felt, max: u64) -> Result<u64, Error> {
+    u64::try_from(value)
+        .ok()
+        .filter(|value| *value <= max)
+        .ok_or(Error::ValueTooLarge {
+            field,
+            value,
+            max: max.into(),
+        })
+}
+
+#[cfg(all(
+    test,
+    feature = "recursive",
+    feature = "keccak_160_lsb",
+    feature = "stone5"
+))]
+mod tests {
+    use swiftness::{commit::Error as CommitError, oods::OodsVerifyError};
+    use swiftness_pow::pow::Error as PowError;
+
+    use super::*;
+    use crate::{crypto::DecommitError, fixtures};
+
+    fn split() -> SplitProof {
+        split_proof_dyn(fixtures::proof(), fixtures::verifier_config()).unwrap()
+    }
+
+    #[test]
+    fn resume_matches_uninterrupted_run() {
+        let job_id = Felt::from(42);
+        let calls = fixtures::calls(job_id);
+        let n_layers = calls.intermediate_steps.len();
+
+        for layer in 0..=n_layers {
+            let state = calls
+                .intermediate_steps
+                .get(layer)
+                .map_or(&calls.final_step.state_variable, |step| {
+                    &step.state_variable
+                });
+
+            for remaining in [
+                split().resume_from_layer(layer, job_id).unwrap(),
+                split().resume_from_state(state, job_id).unwrap(),
+            ] {
+                assert_eq!(
+                    remaining.intermediate_steps,
+                    calls.intermediate_steps[layer..]
+                );
+                assert_eq!(remaining.final_step, calls.final_step);
+            }
+        }
+    }
+
+    #[test]
+    fn resume_rejects_out_of_range_layer() {
+        let mut split = split();
+        let n_layers = split.step_iter.n_layers();
+        split.step_iter.skip_to_layer(1).unwrap();
+
+        assert!(matches!(
+            split.step_iter.skip_to_layer(0),
+            Err(Error::ResumeLayerOutOfRange { layer: 0, .. })
+        ));
+        assert!(matches!(
+            split.step_iter.skip_to_layer(n_layers + 1),
+            Err(Error::ResumeLayerOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn resume_rejects_mismatched_queries() {
+        let calls = fixtures::calls(Felt::ONE);
+        let state = &calls.intermediate_steps[2].state_variable;
+        let n_queries = state.queries.len();
+
+        let mut missing = state.clone();
+        missing.queries.pop();
+        let mut extra = state.clone();
+        extra.queries.push(extra.queries[n_queries - 1].clone());
+        let mut unsorted = state.clone();
+        unsorted.queries.swap(0, 1);
+
+        for (state, query) in [(missing, n_queries - 1), (extra, n_queries), (unsorted, 0)] {
+            assert!(matches!(
+                split().resume_from_state(&state, Felt::ONE),
+                Err(Error::ResumeStateMismatch { layer: 2, query: q }) if q == query
+            ));
+        }
+    }
+
+    #[test]
+    fn verifies_fixture() {
+        split_and_verify_dyn(fixtures::proof(), fixtures::verifier_config()).unwrap();
+        split_and_verify::<swiftness_air::layout::recursive::Layout>(
+            fixtures::proof(),
+            fixtures::verifier_config(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn rejects_tampered_trace_value() {
+        let mut proof = fixtures::proof();
+        proof.witness.traces_decommitment.original.values[0] += Felt::ONE;
+        let commitment = proof.unsent_commitment.traces.original;
+
+        assert!(matches!(
+            split_and_verify_dyn(proof, fixtures::verifier_config()),
+            Err(Error::Decommitment {
+                table: "original trace",
+                error: DecommitError::RootMismatch { expected, .. },
+            }) if expected == commitment
+        ));
+    }
+
+    #[test]
+    fn rejects_tampered_public_input() {
+        let mut proof = fixtures::proof();
+        let last = proof.public_input.main_page.0.len() - 1;
+        proof.public_input.main_page.0[last].value += Felt::ONE;
+
+        // The public input seeds the transcript, so the OODS point and the evaluations change.
+        assert!(matches!(
+            split_and_verify_dyn(proof, fixtures::verifier_config()),
+            Err(Error::Commit(CommitError::Oods(
+                OodsVerifyError::EvaluationInvalid { .. }
+            )))
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_pow_nonce() {
+        let mut proof = fixtures::proof();
+        proof.unsent_commitment.proof_of_work.nonce += 1;
+
+        assert!(matches!(
+            split_and_verify_dyn(proof, fixtures::verifier_config()),
+            Err(Error::Commit(CommitError::POW(PowError::ProofOfWorkFail)))
+        ));
     }
 }
@@ -0,0 +1,615 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use starknet_core::types::Felt;
+
+use crate::{fact::ProofFact, split::MaterializedSplitProof, IntegrityCalls};
+
+/// Extension of job record files in [`FileJobStore`].
+const JOB_FILE_EXTENSION: &str = "json";
+
+/// Extension of the files locking job records in [`FileJobStore`].
+const LOCK_FILE_EXTENSION: &str = "lock";
+
+/// Counter making temporary file names unique within the process.
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A verification job and its submission progress, as persisted by a [`JobStore`].
+///
+/// The split proof is stored with all steps materialized, so that resuming a job doesn't require
+/// recomputing the FRI layers.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct JobRecord {
+    /// ID of the job.
+    pub job_id: Felt,
+    /// Address of the `integrity` contract the job is submitted to.
+    pub contract_address: Felt,
+    /// The split proof, including the verifier configuration.
+    pub split_proof: MaterializedSplitProof,
+    /// Submission progress of the initial call, each intermediate step and the final call, in
+    /// order.
+    pub calls: Vec<CallRecord>,
+    /// The registered fact, once the final call has been accepted.
+    pub fact: Option<ProofFact>,
+}
+
+/// Submission progress of a single call of a job.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CallRecord {
+    /// Submission status of the call.
+    pub status: CallStatus,
+    /// Hash of the latest transaction sending the call.
+    pub transaction_hash: Option<Felt>,
+}
+
+/// Submission status of a call.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum CallStatus {
+    /// The call has not been sent yet.
+    NotSubmitted,
+    /// The call has been sent but not accepted yet.
+    Submitted,
+    /// The call has been accepted.
+    Accepted,
+    /// The call has been accepted but reverted.
+    Reverted { reason: String },
+}
+
+/// Persistent storage of verification jobs, allowing workers to pick up unfinished jobs after a
+/// restart.
+pub trait JobStore {
+    /// Stores a new job, failing if a job with the same ID exists.
+    fn insert_job(&mut self, record: &JobRecord) -> Result<(), StoreError>;
+
+    /// Gets a job by ID.
+    fn get_job(&self, job_id: Felt) -> Result<Option<JobRecord>, StoreError>;
+
+    /// Updates the submission progress of the call at `index` in a job.
+    fn update_call(
+        &mut self,
+        job_id: Felt,
+        index: usize,
+        call: &CallRecord,
+    ) -> Result<(), StoreError>;
+
+    /// Marks a job as finished with the fact it registered.
+    fn complete_job(&mut self, job_id: Felt, fact: ProofFact) -> Result<(), StoreError>;
+
+    /// Gets all jobs without a registered fact.
+    fn unfinished_jobs(&self) -> Result<Vec<JobRecord>, StoreError>;
+}
+
+/// A [`JobStore`] keeping each job in its own JSON file within a directory.
+///
+/// Jobs can be shared by several processes: updates to a job are serialized by a lock file next to
+/// its record.
+#[derive(Debug, Clone)]
+pub struct FileJobStore {
+    dir: PathBuf,
+}
+
+/// An exclusive lock on a job of a [`FileJobStore`], released when dropped.
+#[derive(Debug)]
+struct JobLock {
+    path: PathBuf,
+}
+
+/// A [`JobStore`] backed by a SQLite database.
+#[cfg(feature = "sqlite")]
+#[derive(Debug)]
+pub struct SqliteJobStore {
+    connection: rusqlite::Connection,
+}
+
+/// Errors from persisting jobs in a [`JobStore`].
+#[derive(Debug, thiserror::Error)]
+pub enum StoreError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[cfg(feature = "sqlite")]
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("job {0:#066x} already exists")]
+    JobAlreadyExists(Felt),
+    #[error("job {0:#066x} does not exist")]
+    JobNotFound(Felt),
+    #[error("job {0:#066x} is locked by another writer")]
+    JobLocked(Felt),
+    #[error("call {index} is out of range for job {job_id:#066x} with {n_calls} calls")]
+    CallOutOfRange {
+        job_id: Felt,
+        index: usize,
+        n_calls: usize,
+    },
+    #[error("invalid stored value `{0}`")]
+    InvalidValue(String),
+}
+
+impl JobRecord {
+    /// Creates a new [`JobRecord`] with no call submitted yet.
+    pub fn new(job_id: Felt, contract_address: Felt, split_proof: MaterializedSplitProof) -> Self {
+        // The initial and final calls surround the intermediate steps.
+        let n_calls = split_proof.steps.len() + 2;
+
+        Self {
+            job_id,
+            contract_address,
+            split_proof,
+            calls: vec![
+                CallRecord {
+                    status: CallStatus::NotSubmitted,
+                    transaction_hash: None,
+                };
+                n_calls
+            ],
+            fact: None,
+        }
+    }
+
+    /// Builds the contract calls of the job.
+    pub fn integrity_calls(&self) -> IntegrityCalls {
+        self.split_proof.clone().into_calls(self.job_id)
+    }
+
+    /// Gets the index of the first call not accepted yet, or `None` if all calls are accepted.
+    pub fn next_call(&self) -> Option<usize> {
+        self.calls
+            .iter()
+            .position(|call| call.status != CallStatus::Accepted)
+    }
+
+    fn call_mut(&mut self, index: usize) -> Result<&mut CallRecord, StoreError> {
+        let n_calls = self.calls.len();
+        self.calls.get_mut(index).ok_or(StoreError::CallOutOfRange {
+            job_id: self.job_id,
+            index,
+            n_calls,
+        })
+    }
+}
+
+impl FileJobStore {
+    /// Opens a [`FileJobStore`] in `dir`, creating the directory if needed.
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self, StoreError> {
+        let dir = dir.as_ref().to_owned();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, job_id: Felt) -> PathBuf {
+        self.dir
+            .join(format!("{job_id:#066x}"))
+            .with_extension(JOB_FILE_EXTENSION)
+    }
+
+    fn read(&self, job_id: Felt) -> Result<JobRecord, StoreError> {
+        self.get_job(job_id)?.ok_or(StoreError::JobNotFound(job_id))
+    }
+
+    /// Takes the exclusive lock of the job `job_id`, held until the returned guard is dropped.
+    ///
+    /// Locking fails right away if another writer holds the lock. A writer that crashes while
+    /// holding it leaves the `.lock` file behind, which must then be removed manually.
+    fn lock(&self, job_id: Felt) -> Result<JobLock, StoreError> {
+        let path = self.path(job_id).with_extension(LOCK_FILE_EXTENSION);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => Ok(JobLock { path }),
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+                Err(StoreError::JobLocked(job_id))
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Writes to a temporary file first so that a crash never leaves a truncated record behind.
+    fn write(&self, record: &JobRecord) -> Result<(), StoreError> {
+        let temp_path = self.write_temp(record)?;
+        fs::rename(&temp_path, self.path(record.job_id))?;
+        Ok(())
+    }
+
+    /// Writes `record` to a new temporary file, unique across concurrent writers.
+    fn write_temp(&self, record: &JobRecord) -> Result<PathBuf, StoreError> {
+        let temp_path = self.path(record.job_id).with_extension(format!(
+            "{}.{}.tmp",
+            std::process::id(),
+            TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temp_path, serde_json::to_vec(record)?)?;
+        Ok(temp_path)
+    }
+}
+
+impl Drop for JobLock {
+    fn drop(&mut self) {
+        // A lock left behind only blocks later updates, which report it.
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+impl JobStore for FileJobStore {
+    fn insert_job(&mut self, record: &JobRecord) -> Result<(), StoreError> {
+        // Linking fails if the record exists, so concurrent inserts can't overwrite each other.
+        let temp_path = self.write_temp(record)?;
+        let linked = fs::hard_link(&temp_path, self.path(record.job_id));
+        fs::remove_file(&temp_path)?;
+
+        match linked {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+                Err(StoreError::JobAlreadyExists(record.job_id))
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_job(&self, job_id: Felt) -> Result<Option<JobRecord>, StoreError> {
+        match fs::read(self.path(job_id)) {
+            Ok(content) => Ok(Some(serde_json::from_slice(&content)?)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn update_call(
+        &mut self,
+        job_id: Felt,
+        index: usize,
+        call: &CallRecord,
+    ) -> Result<(), StoreError> {
+        let _lock = self.lock(job_id)?;
+        let mut record = self.read(job_id)?;
+        *record.call_mut(index)? = call.clone();
+        self.write(&record)
+    }
+
+    fn complete_job(&mut self, job_id: Felt, fact: ProofFact) -> Result<(), StoreError> {
+        let _lock = self.lock(job_id)?;
+        let mut record = self.read(job_id)?;
+        record.fact = Some(fact);
+        self.write(&record)
+    }
+
+    fn unfinished_jobs(&self) -> Result<Vec<JobRecord>, StoreError> {
+        let mut jobs = vec![];
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(JOB_FILE_EXTENSION) {
+                continue;
+            }
+
+            let record: JobRecord = serde_json::from_slice(&fs::read(&path)?)?;
+            if record.fact.is_none() {
+                jobs.push(record);
+            }
+        }
+
+        // Directory listing order is unspecified.
+        jobs.sort_by_key(|record| record.job_id);
+        Ok(jobs)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteJobStore {
+    /// Opens a [`SqliteJobStore`] at `path`, creating the database and tables if needed.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, StoreError> {
+        Self::new(rusqlite::Connection::open(path)?)
+    }
+
+    /// Creates a [`SqliteJobStore`] from an existing connection, creating the tables if needed.
+    pub fn new(connection: rusqlite::Connection) -> Result<Self, StoreError> {
+        // Call progress lives in its own table so that updating it doesn't rewrite the proof.
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS jobs (
+                job_id TEXT PRIMARY KEY,
+                contract_address TEXT NOT NULL,
+                split_proof TEXT NOT NULL,
+                fact TEXT
+            );
+            CREATE TABLE IF NOT EXISTS calls (
+                job_id TEXT NOT NULL REFERENCES jobs (job_id),
+                call_index INTEGER NOT NULL,
+                status TEXT NOT NULL,
+                transaction_hash TEXT,
+                PRIMARY KEY (job_id, call_index)
+            );",
+        )?;
+
+        Ok(Self { connection })
+    }
+
+    fn get_job_by_key(&self, job_id: &str) -> Result<Option<JobRecord>, StoreError> {
+        use rusqlite::OptionalExtension;
+
+        let Some((contract_address, split_proof, fact)) = self
+            .connection
+            .query_row(
+                "SELECT contract_address, split_proof, fact FROM jobs WHERE job_id = ?1",
+                [job_id],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                    ))
+                },
+            )
+            .optional()?
+        else {
+            return Ok(None);
+        };
+
+        let mut statement = self.connection.prepare(
+            "SELECT status, transaction_hash FROM calls WHERE job_id = ?1 ORDER BY call_index",
+        )?;
+        let calls = statement
+            .query_map([job_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+            })?
+            .map(|row| {
+                let (status, transaction_hash) = row?;
+                Ok(CallRecord {
+                    status: serde_json::from_str(&status)?,
+                    transaction_hash: transaction_hash.as_deref().map(parse_felt).transpose()?,
+                })
+            })
+            .collect::<Result<_, StoreError>>()?;
+
+        Ok(Some(JobRecord {
+            job_id: parse_felt(job_id)?,
+            contract_address: parse_felt(&contract_address)?,
+            split_proof: serde_json::from_str(&split_proof)?,
+            calls,
+            fact: fact.as_deref().map(serde_json::from_str).transpose()?,
+        }))
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl JobStore for SqliteJobStore {
+    fn insert_job(&mut self, record: &JobRecord) -> Result<(), StoreError> {
+        let job_id = format_felt(record.job_id);
+
+        let transaction = self.connection.transaction()?;
+        let exists = transaction
+            .prepare("SELECT 1 FROM jobs WHERE job_id = ?1")?
+            .exists([&job_id])?;
+        if exists {
+            return Err(StoreError::JobAlreadyExists(record.job_id));
+        }
+
+        transaction.execute(
+            "INSERT INTO jobs (job_id, contract_address, split_proof, fact) VALUES (?1, ?2, ?3, ?4)",
+            (
+                &job_id,
+                format_felt(record.contract_address),
+                serde_json::to_string(&record.split_proof)?,
+                record.fact.as_ref().map(serde_json::to_string).transpose()?,
+            ),
+        )?;
+        for (index, call) in record.calls.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO calls (job_id, call_index, status, transaction_hash) \
+                VALUES (?1, ?2, ?3, ?4)",
+                (
+                    &job_id,
+                    index,
+                    serde_json::to_string(&call.status)?,
+                    call.transaction_hash.map(format_felt),
+                ),
+            )?;
+        }
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    fn get_job(&self, job_id: Felt) -> Result<Option<JobRecord>, StoreError> {
+        self.get_job_by_key(&format_felt(job_id))
+    }
+
+    fn update_call(
+        &mut self,
+        job_id: Felt,
+        index: usize,
+        call: &CallRecord,
+    ) -> Result<(), StoreError> {
+        let updated = self.connection.execute(
+            "UPDATE calls SET status = ?3, transaction_hash = ?4 \
+            WHERE job_id = ?1 AND call_index = ?2",
+            (
+                format_felt(job_id),
+                index,
+                serde_json::to_string(&call.status)?,
+                call.transaction_hash.map(format_felt),
+            ),
+        )?;
+
+        if updated == 0 {
+            let record = self
+                .get_job(job_id)?
+                .ok_or(StoreError::JobNotFound(job_id))?;
+            return Err(StoreError::CallOutOfRange {
+                job_id,
+                index,
+                n_calls: record.calls.len(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn complete_job(&mut self, job_id: Felt, fact: ProofFact) -> Result<(), StoreError> {
+        let updated = self.connection.execute(
+            "UPDATE jobs SET fact = ?2 WHERE job_id = ?1",
+            (format_felt(job_id), serde_json::to_string(&fact)?),
+        )?;
+
+        if updated == 0 {
+            return Err(StoreError::JobNotFound(job_id));
+        }
+
+        Ok(())
+    }
+
+    fn unfinished_jobs(&self) -> Result<Vec<JobRecord>, StoreError> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT job_id FROM jobs WHERE fact IS NULL ORDER BY job_id")?;
+        let job_ids = statement
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        job_ids
+            .iter()
+            .filter_map(|job_id| self.get_job_by_key(job_id).transpose())
+            .collect()
+    }
+}
+
+/// Formats a felt as zero-padded hex, so that textual order matches numerical order.
+#[cfg(feature = "sqlite")]
+fn format_felt(value: Felt) -> String {
+    format!("{value:#066x}")
+}
+
+#[cfg(feature = "sqlite")]
+fn parse_felt(value: &str) -> Result<Felt, StoreError> {
+    Felt::from_hex(value).map_err(|_| StoreError::InvalidValue(value.to_owned()))
+}
+
+#[cfg(all(
+    test,
+    feature = "recursive",
+    feature = "keccak_160_lsb",
+    feature = "stone5"
+))]
+mod tests {
+    use super::*;
+
+    use crate::{fact::compute_verification, fixtures, split::split_proof_dyn};
+
+    fn record(job_id: Felt) -> JobRecord {
+        let split_proof = split_proof_dyn(fixtures::proof(), fixtures::verifier_config())
+            .unwrap()
+            .materialize()
+            .unwrap();
+        JobRecord::new(job_id, Felt::from(0x1234), split_proof)
+    }
+
+    fn check_store<S: JobStore>(store: &mut S) {
+        let first = record(Felt::ONE);
+        let second = record(Felt::TWO);
+        store.insert_job(&second).unwrap();
+        store.insert_job(&first).unwrap();
+        assert!(matches!(
+            store.insert_job(&first),
+            Err(StoreError::JobAlreadyExists(job_id)) if job_id == Felt::ONE
+        ));
+
+        assert_eq!(store.get_job(Felt::ONE).unwrap().as_ref(), Some(&first));
+        assert_eq!(store.get_job(Felt::THREE).unwrap(), None);
+        assert_eq!(first.next_call(), Some(0));
+
+        let accepted = CallRecord {
+            status: CallStatus::Accepted,
+            transaction_hash: Some(Felt::from(0xabcd)),
+        };
+        store.update_call(Felt::ONE, 0, &accepted).unwrap();
+        let updated = store.get_job(Felt::ONE).unwrap().unwrap();
+        assert_eq!(updated.calls[0], accepted);
+        assert_eq!(updated.calls[1..], first.calls[1..]);
+        assert_eq!(updated.next_call(), Some(1));
+
+        let n_calls = first.calls.len();
+        assert!(matches!(
+            store.update_call(Felt::ONE, n_calls, &accepted),
+            Err(StoreError::CallOutOfRange { index, .. }) if index == n_calls
+        ));
+        assert!(matches!(
+            store.update_call(Felt::THREE, 0, &accepted),
+            Err(StoreError::JobNotFound(_))
+        ));
+
+        let fact = compute_verification(&fixtures::proof(), &fixtures::verifier_config())
+            .unwrap()
+            .fact;
+        store.complete_job(Felt::TWO, fact).unwrap();
+        assert_eq!(store.get_job(Felt::TWO).unwrap().unwrap().fact, Some(fact));
+        assert!(matches!(
+            store.complete_job(Felt::THREE, fact),
+            Err(StoreError::JobNotFound(_))
+        ));
+
+        assert_eq!(store.unfinished_jobs().unwrap(), [updated]);
+    }
+
+    #[test]
+    fn file_job_store() {
+        let dir = std::env::temp_dir().join(format!("integrity-job-store-{}", std::process::id()));
+        let mut store = FileJobStore::open(&dir).unwrap();
+
+        check_store(&mut store);
+
+        // No temporary files are left behind.
+        let n_files = fs::read_dir(&dir).unwrap().count();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(n_files, 2);
+    }
+
+    #[test]
+    fn file_job_store_locks_updates() {
+        let dir = std::env::temp_dir().join(format!("integrity-job-lock-{}", std::process::id()));
+        let mut store = FileJobStore::open(&dir).unwrap();
+        let record = record(Felt::ONE);
+        store.insert_job(&record).unwrap();
+
+        let accepted = CallRecord {
+            status: CallStatus::Accepted,
+            transaction_hash: Some(Felt::from(0xabcd)),
+        };
+        let lock = store.lock(Felt::ONE).unwrap();
+        assert!(matches!(
+            store.update_call(Felt::ONE, 0, &accepted),
+            Err(StoreError::JobLocked(job_id)) if job_id == Felt::ONE
+        ));
+        drop(lock);
+
+        // Concurrent writers updating different calls of the same job don't lose updates.
+        let threads = (0..record.calls.len())
+            .map(|index| {
+                let mut store = store.clone();
+                let accepted = accepted.clone();
+                std::thread::spawn(move || loop {
+                    match store.update_call(Felt::ONE, index, &accepted) {
+                        Err(StoreError::JobLocked(_)) => std::thread::yield_now(),
+                        result => break result.unwrap(),
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let updated = store.get_job(Felt::ONE).unwrap().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(updated.calls.iter().all(|call| *call == accepted));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_job_store() {
+        let mut store =
+            SqliteJobStore::new(rusqlite::Connection::open_in_memory().unwrap()).unwrap();
+
+        check_store(&mut store);
+    }
+}
@@ -0,0 +1,647 @@
+use std::{thread, time::Duration};
+
+use starknet_core::types::{Call, Felt};
+
+use crate::{
+    invoke::{InvokeError, InvokeTransactionBuilder, LocalSigner, SignedInvokeTransaction},
+    IntegrityCalls,
+};
+
+/// Network access needed by [`Orchestrator`] to submit transactions and follow their outcome.
+///
+/// Implementations decide which failures are worth retrying by returning
+/// [`ProviderError::Transient`].
+pub trait SubmissionProvider {
+    /// Gets the next nonce of the account at `address`.
+    fn nonce(&mut self, address: Felt) -> Result<Felt, ProviderError>;
+
+    /// Broadcasts a signed transaction.
+    fn add_invoke_transaction(
+        &mut self,
+        transaction: &SignedInvokeTransaction,
+    ) -> Result<(), ProviderError>;
+
+    /// Gets the status of a previously broadcast transaction.
+    fn transaction_status(
+        &mut self,
+        transaction_hash: Felt,
+    ) -> Result<TransactionStatus, ProviderError>;
+
+    /// Gets how far the verification job `job_id` has progressed on the `integrity` contract at
+    /// `contract_address`.
+    fn job_progress(
+        &mut self,
+        contract_address: Felt,
+        job_id: Felt,
+    ) -> Result<JobProgress, ProviderError>;
+}
+
+/// Status of a broadcast transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionStatus {
+    /// The transaction has not been accepted yet.
+    Pending,
+    /// The transaction has been accepted and executed successfully.
+    Accepted,
+    /// The transaction has been accepted but its execution reverted.
+    Reverted { reason: String },
+    /// The transaction has been dropped without being accepted.
+    Rejected { reason: String },
+}
+
+/// Progress of a verification job on the `integrity` contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobProgress {
+    /// The initial call has not been applied.
+    NotStarted,
+    /// The initial call has been applied, with the FRI layer `next_layer` to be verified next. The
+    /// final call is next once `next_layer` reaches the number of layers.
+    InProgress { next_layer: u32 },
+    /// The final call has been applied and the fact registered.
+    Completed,
+}
+
+/// Errors returned by a [`SubmissionProvider`].
+#[derive(Debug, thiserror::Error)]
+pub enum ProviderError {
+    #[error("transient provider failure: {0}")]
+    Transient(String),
+    #[error("transaction fee is too low")]
+    FeeTooLow,
+    #[error("transaction nonce {0:#x} is not the next nonce of the account")]
+    InvalidNonce(Felt),
+    #[error("provider failure: {0}")]
+    Other(String),
+}
+
+/// Settings controlling how [`Orchestrator`] retries and bumps fees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubmissionConfig {
+    /// Maximum number of retries after transient failures, across the whole job.
+    pub max_retries: u32,
+    /// Maximum number of fee bumps, across the whole job.
+    pub max_fee_bumps: u32,
+    /// Percentage by which each fee bump raises the max price per unit of all resources.
+    pub fee_bump_percent: u64,
+    /// Number of times a transaction status is polled before bumping its fee.
+    pub max_polls: u32,
+    /// Delay between status polls and retries.
+    pub poll_interval: Duration,
+}
+
+/// Drives all calls of a verification job to completion, one transaction per call.
+///
+/// Calls already applied on-chain, as reported by [`SubmissionProvider::job_progress`], are
+/// skipped, which allows resuming interrupted jobs and running against jobs partially submitted
+/// elsewhere.
+#[derive(Debug)]
+pub struct Orchestrator<P> {
+    provider: P,
+    builder: InvokeTransactionBuilder,
+    signer: LocalSigner,
+    config: SubmissionConfig,
+    retries: u32,
+    fee_bumps: u32,
+}
+
+/// Summary of a job driven to completion by [`Orchestrator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmissionReport {
+    /// Hashes of the accepted transactions, in submission order.
+    pub transaction_hashes: Vec<Felt>,
+    /// Number of calls skipped as they had already been applied.
+    pub skipped_calls: usize,
+    /// Number of retries after transient failures.
+    pub retries: u32,
+    /// Number of fee bumps.
+    pub fee_bumps: u32,
+}
+
+/// Errors from driving a verification job with [`Orchestrator`].
+#[derive(Debug, thiserror::Error)]
+pub enum SubmissionError {
+    #[error(transparent)]
+    Invoke(#[from] InvokeError),
+    #[error("call {index} failed: {error}")]
+    Provider { index: usize, error: ProviderError },
+    #[error("call {index} failed after {retries} retries: {error}")]
+    RetriesExhausted {
+        index: usize,
+        retries: u32,
+        error: ProviderError,
+    },
+    #[error("call {index} not accepted after {fee_bumps} fee bumps")]
+    FeeBumpsExhausted { index: usize, fee_bumps: u32 },
+    #[error("call {index} reverted: {reason}")]
+    Reverted { index: usize, reason: String },
+    #[error("job progress {progress:?} does not match any call of the job")]
+    UnexpectedProgress { progress: JobProgress },
+}
+
+/// What to do after a submission attempt that didn't get the call applied.
+enum Retry {
+    Transient(ProviderError),
+    BumpFee,
+}
+
+impl Default for SubmissionConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 10,
+            max_fee_bumps: 5,
+            fee_bump_percent: 20,
+            max_polls: 60,
+            poll_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+impl<P> Orchestrator<P>
+where
+    P: SubmissionProvider,
+{
+    /// Creates a new [`Orchestrator`] sending transactions built by `builder` and signed by
+    /// `signer`.
+    pub fn new(
+        provider: P,
+        builder: InvokeTransactionBuilder,
+        signer: LocalSigner,
+        config: SubmissionConfig,
+    ) -> Self {
+        Self {
+            provider,
+            builder,
+            signer,
+            config,
+            retries: 0,
+            fee_bumps: 0,
+        }
+    }
+
+    /// Gets the provider, e.g. to inspect a mock after a run.
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Consumes the orchestrator, returning the provider.
+    pub fn into_provider(self) -> P {
+        self.provider
+    }
+
+    /// Submits the calls of a verification job against the `integrity` contract at
+    /// `contract_address`, waiting for each transaction to be accepted before sending the next.
+    pub fn run(
+        &mut self,
+        calls: IntegrityCalls,
+        contract_address: Felt,
+    ) -> Result<SubmissionReport, SubmissionError> {
+        let job_id = calls.initial.job_id;
+        let layers = calls
+            .intermediate_steps
+            .iter()
+            .map(|step| step.state_variable.iter)
+            .chain([calls.final_step.state_variable.iter])
+            .collect::<Vec<_>>();
+        let calls = calls.collect_calls(contract_address);
+
+        self.retries = 0;
+        self.fee_bumps = 0;
+
+        let mut report = SubmissionReport {
+            transaction_hashes: vec![],
+            skipped_calls: 0,
+            retries: 0,
+            fee_bumps: 0,
+        };
+
+        let mut index = 0;
+        while index < calls.len() {
+            // Checked before every call so that calls applied by an earlier attempt, or by another
+            // submitter, are never sent twice.
+            let next_index = self.next_call_index(contract_address, job_id, &layers, index)?;
+            if next_index > index {
+                report.skipped_calls += next_index - index;
+                index = next_index;
+                continue;
+            }
+
+            if let Some(transaction_hash) =
+                self.submit_call(&calls[index], index, contract_address, job_id, &layers)?
+            {
+                report.transaction_hashes.push(transaction_hash);
+            }
+            index += 1;
+        }
+
+        report.retries = self.retries;
+        report.fee_bumps = self.fee_bumps;
+        Ok(report)
+    }
+
+    /// Sends a single call until it's applied, returning the hash of the accepted transaction, or
+    /// `None` if the call turned out to be applied by someone else.
+    fn submit_call(
+        &mut self,
+        call: &Call,
+        index: usize,
+        contract_address: Felt,
+        job_id: Felt,
+        layers: &[u32],
+    ) -> Result<Option<Felt>, SubmissionError> {
+        loop {
+            let retry = match self.try_submit_call(call, index)? {
+                Ok(transaction_hash) => return Ok(Some(transaction_hash)),
+                Err(retry) => retry,
+            };
+
+            match retry {
+                Retry::Transient(error) => {
+                    if self.retries >= self.config.max_retries {
+                        return Err(SubmissionError::RetriesExhausted {
+                            index,
+                            retries: self.retries,
+                            error,
+                        });
+                    }
+                    self.retries += 1;
+                    thread::sleep(self.config.poll_interval);
+                }
+                Retry::BumpFee => {
+                    if self.fee_bumps >= self.config.max_fee_bumps {
+                        return Err(SubmissionError::FeeBumpsExhausted {
+                            index,
+                            fee_bumps: self.fee_bumps,
+                        });
+                    }
+                    self.fee_bumps += 1;
+                    self.builder.bump_prices(self.config.fee_bump_percent);
+                }
+            }
+
+            // A transaction from a previous attempt may have landed in the meantime.
+            if self.next_call_index(contract_address, job_id, layers, index)? > index {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Makes a single attempt at getting a call applied, returning how to retry on failure.
+    fn try_submit_call(
+        &mut self,
+        call: &Call,
+        index: usize,
+    ) -> Result<Result<Felt, Retry>, SubmissionError> {
+        let nonce = match self.provider.nonce(self.builder.sender_address()) {
+            Ok(nonce) => nonce,
+            Err(error) => return self.classify(index, error),
+        };
+
+        let transaction = self
+            .builder
+            .sign(std::slice::from_ref(call), nonce, &self.signer)?;
+        if let Err(error) = self.provider.add_invoke_transaction(&transaction) {
+            return self.classify(index, error);
+        }
+
+        for poll in 0..self.config.max_polls {
+            if poll != 0 {
+                thread::sleep(self.config.poll_interval);
+            }
+
+            match self
+                .provider
+                .transaction_status(transaction.transaction_hash)
+            {
+                Ok(TransactionStatus::Pending) => {}
+                Ok(TransactionStatus::Accepted) => return Ok(Ok(transaction.transaction_hash)),
+                Ok(TransactionStatus::Reverted { reason }) => {
+                    return Err(SubmissionError::Reverted { index, reason })
+                }
+                Ok(TransactionStatus::Rejected { reason }) => {
+                    return Ok(Err(Retry::Transient(ProviderError::Other(reason))))
+                }
+                Err(error) => return self.classify(index, error),
+            }
+        }
+
+        // Still pending: replace the transaction with a better paying one.
+        Ok(Err(Retry::BumpFee))
+    }
+
+    fn classify(
+        &self,
+        index: usize,
+        error: ProviderError,
+    ) -> Result<Result<Felt, Retry>, SubmissionError> {
+        match error {
+            ProviderError::Transient(_) | ProviderError::InvalidNonce(_) => {
+                Ok(Err(Retry::Transient(error)))
+            }
+            ProviderError::FeeTooLow => Ok(Err(Retry::BumpFee)),
+            ProviderError::Other(_) => Err(SubmissionError::Provider { index, error }),
+        }
+    }
+
+    /// Finds the index of the first call not applied yet, based on the job progress. `layers`
+    /// holds the FRI layer verified by each call after the initial one.
+    fn next_call_index(
+        &mut self,
+        contract_address: Felt,
+        job_id: Felt,
+        layers: &[u32],
+        index: usize,
+    ) -> Result<usize, SubmissionError> {
+        let progress = loop {
+            match self.provider.job_progress(contract_address, job_id) {
+                Ok(progress) => break progress,
+                Err(error @ ProviderError::Transient(_)) => {
+                    if self.retries >= self.config.max_retries {
+                        return Err(SubmissionError::RetriesExhausted {
+                            index,
+                            retries: self.retries,
+                            error,
+                        });
+                    }
+                    self.retries += 1;
+                    thread::sleep(self.config.poll_interval);
+                }
+                // Reading the job progress involves neither fees nor nonces, so these can't be
+                // fixed by bumping the fee or retrying.
+                Err(
+                    error @ (ProviderError::FeeTooLow
+                    | ProviderError::InvalidNonce(_)
+                    | ProviderError::Other(_)),
+                ) => return Err(SubmissionError::Provider { index, error }),
+            }
+        };
+
+        match progress {
+            JobProgress::NotStarted => Ok(0),
+            JobProgress::InProgress { next_layer } => layers
+                .iter()
+                .position(|layer| *layer == next_layer)
+                .map(|position| position + 1)
+                .ok_or(SubmissionError::UnexpectedProgress { progress }),
+            JobProgress::Completed => Ok(layers.len() + 1),
+        }
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "recursive",
+    feature = "keccak_160_lsb",
+    feature = "stone5"
+))]
+mod tests {
+    use std::collections::VecDeque;
+
+    use starknet_core::types::{ResourceBounds, ResourceBoundsMapping};
+
+    use super::*;
+    use crate::{fixtures, mock::MockProvider};
+
+    const CONTRACT_ADDRESS: Felt = Felt::from_hex_unchecked("0x1234");
+
+    /// Wraps a [`MockProvider`], failing requests as scripted.
+    struct ScriptedProvider {
+        inner: MockProvider,
+        /// Outcomes of the next `add_invoke_transaction` requests, with `None` forwarding the
+        /// request.
+        add_errors: VecDeque<Option<ProviderError>>,
+        /// Errors returned by the next `job_progress` requests.
+        progress_errors: VecDeque<ProviderError>,
+        /// Max L2 gas price of each forwarded transaction.
+        l2_gas_prices: Vec<u128>,
+    }
+
+    impl ScriptedProvider {
+        fn new(inner: MockProvider) -> Self {
+            Self {
+                inner,
+                add_errors: VecDeque::new(),
+                progress_errors: VecDeque::new(),
+                l2_gas_prices: vec![],
+            }
+        }
+    }
+
+    impl SubmissionProvider for ScriptedProvider {
+        fn nonce(&mut self, address: Felt) -> Result<Felt, ProviderError> {
+            self.inner.nonce(address)
+        }
+
+        fn add_invoke_transaction(
+            &mut self,
+            transaction: &SignedInvokeTransaction,
+        ) -> Result<(), ProviderError> {
+            if let Some(error) = self.add_errors.pop_front().flatten() {
+                return Err(error);
+            }
+            self.l2_gas_prices.push(
+                transaction
+                    .transaction
+                    .resource_bounds
+                    .l2_gas
+                    .max_price_per_unit,
+            );
+            self.inner.add_invoke_transaction(transaction)
+        }
+
+        fn transaction_status(
+            &mut self,
+            transaction_hash: Felt,
+        ) -> Result<TransactionStatus, ProviderError> {
+            self.inner.transaction_status(transaction_hash)
+        }
+
+        fn job_progress(
+            &mut self,
+            contract_address: Felt,
+            job_id: Felt,
+        ) -> Result<JobProgress, ProviderError> {
+            match self.progress_errors.pop_front() {
+                Some(error) => Err(error),
+                None => self.inner.job_progress(contract_address, job_id),
+            }
+        }
+    }
+
+    fn orchestrator(provider: ScriptedProvider) -> Orchestrator<ScriptedProvider> {
+        let bounds = ResourceBounds {
+            max_amount: 1_000_000,
+            max_price_per_unit: 100,
+        };
+        Orchestrator::new(
+            provider,
+            InvokeTransactionBuilder::new(
+                Felt::from_hex_unchecked("0x5678"),
+                starknet_core::chain_id::SEPOLIA,
+                ResourceBoundsMapping {
+                    l1_gas: bounds.clone(),
+                    l2_gas: bounds,
+                },
+            ),
+            LocalSigner::new(Felt::from_hex_unchecked("0x9abc")),
+            SubmissionConfig {
+                max_retries: 2,
+                poll_interval: Duration::ZERO,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn scripted_provider() -> ScriptedProvider {
+        ScriptedProvider::new(MockProvider::new(CONTRACT_ADDRESS))
+    }
+
+    #[test]
+    fn submits_all_calls() {
+        let calls = fixtures::calls(Felt::ONE);
+        let n_calls = calls.intermediate_steps.len() + 2;
+
+        let mut orchestrator = orchestrator(scripted_provider());
+        let report = orchestrator.run(calls, CONTRACT_ADDRESS).unwrap();
+
+        assert_eq!(report.transaction_hashes.len(), n_calls);
+        assert_eq!(report.skipped_calls, 0);
+        assert_eq!(report.retries, 0);
+        assert_eq!(report.fee_bumps, 0);
+        let integrity = orchestrator.provider().inner.integrity();
+        assert_eq!(integrity.registered_facts().len(), 1);
+        assert_eq!(integrity.job_progress(Felt::ONE), JobProgress::Completed);
+    }
+
+    #[test]
+    fn retries_transient_failures() {
+        let mut provider = scripted_provider();
+        provider.add_errors = [None, Some(ProviderError::Transient("timeout".into()))].into();
+        provider.progress_errors = [ProviderError::Transient("timeout".into())].into();
+
+        let mut orchestrator = orchestrator(provider);
+        let report = orchestrator
+            .run(fixtures::calls(Felt::ONE), CONTRACT_ADDRESS)
+            .unwrap();
+
+        assert_eq!(report.retries, 2);
+        assert_eq!(
+            orchestrator
+                .provider()
+                .inner
+                .integrity()
+                .registered_facts()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn gives_up_after_max_retries() {
+        let mut provider = scripted_provider();
+        provider.add_errors = (0..3)
+            .map(|_| Some(ProviderError::Transient("timeout".into())))
+            .collect();
+
+        let error = orchestrator(provider)
+            .run(fixtures::calls(Felt::ONE), CONTRACT_ADDRESS)
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            SubmissionError::RetriesExhausted {
+                index: 0,
+                retries: 2,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn bumps_fee_when_too_low() {
+        let mut provider = scripted_provider();
+        provider.add_errors = [Some(ProviderError::FeeTooLow)].into();
+
+        let mut orchestrator = orchestrator(provider);
+        let report = orchestrator
+            .run(fixtures::calls(Felt::ONE), CONTRACT_ADDRESS)
+            .unwrap();
+
+        assert_eq!(report.fee_bumps, 1);
+        // Default bump of 20% over the initial price of 100.
+        assert!(orchestrator
+            .provider()
+            .l2_gas_prices
+            .iter()
+            .all(|price| *price == 120));
+    }
+
+    #[test]
+    fn fails_on_fee_too_low_from_job_progress() {
+        let mut provider = scripted_provider();
+        provider.progress_errors = [ProviderError::FeeTooLow].into();
+
+        let error = orchestrator(provider)
+            .run(fixtures::calls(Felt::ONE), CONTRACT_ADDRESS)
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            SubmissionError::Provider {
+                index: 0,
+                error: ProviderError::FeeTooLow
+            }
+        ));
+    }
+
+    #[test]
+    fn resumes_in_progress_job() {
+        let calls = fixtures::calls(Felt::ONE);
+        let n_calls = calls.intermediate_steps.len() + 2;
+
+        let mut provider = scripted_provider();
+        provider.add_errors = [None, None, None, Some(ProviderError::Other("down".into()))].into();
+        let mut orchestrator = orchestrator(provider);
+        let error = orchestrator
+            .run(calls.clone(), CONTRACT_ADDRESS)
+            .unwrap_err();
+        assert!(matches!(error, SubmissionError::Provider { index: 3, .. }));
+        assert!(matches!(
+            orchestrator
+                .provider()
+                .inner
+                .integrity()
+                .job_progress(Felt::ONE),
+            JobProgress::InProgress { .. }
+        ));
+
+        let report = orchestrator.run(calls, CONTRACT_ADDRESS).unwrap();
+        assert_eq!(report.skipped_calls, 3);
+        assert_eq!(report.transaction_hashes.len(), n_calls - 3);
+        assert_eq!(
+            orchestrator
+                .provider()
+                .inner
+                .integrity()
+                .registered_facts()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn stops_on_reverted_call() {
+        let mut calls = fixtures::calls(Felt::ONE);
+        calls.intermediate_steps[0].witness.leaves[0] += Felt::ONE;
+
+        let mut orchestrator = orchestrator(scripted_provider());
+        let error = orchestrator.run(calls, CONTRACT_ADDRESS).unwrap_err();
+
+        assert!(matches!(error, SubmissionError::Reverted { index: 1, .. }));
+        assert!(orchestrator
+            .provider()
+            .inner
+            .integrity()
+            .registered_facts()
+            .is_empty());
+    }
+}